@@ -12,6 +12,13 @@ fn alert_window(window: tauri::Window) {
         .expect("should be able to request user attention");
 }
 
+#[tauri::command]
+fn warn_window(window: tauri::Window) {
+    window
+        .request_user_attention(Some(tauri::UserAttentionType::Informational))
+        .expect("should be able to request user attention");
+}
+
 #[tauri::command]
 fn contextmenu(window: tauri::Window, ctx: State<GlobalContextMenu>) {
     ctx.0.popup(window).unwrap();
@@ -135,10 +142,28 @@ fn main() {
                     ("blur", "Blur"),
                 ],
             )?;
+            let time_policy_menu = RadioSubmenu::new(
+                app,
+                "time-policy",
+                "Times without am/pm",
+                vec![
+                    ("closest", "Closest"),
+                    ("next", "Next to come"),
+                    ("working-hours", "During working hours"),
+                    ("24h", "24-hour time"),
+                ],
+            )?;
+            let locale_menu = RadioSubmenu::new(
+                app,
+                "locale",
+                "Input language",
+                vec![("en", "English"), ("de", "Deutsch"), ("fr", "Français")],
+            )?;
 
             let menu = MenuBuilder::new(app)
                 .text("add-timer", "Add timer")
                 .text("delete-all", "Delete all timers")
+                .text("export-iso", "Copy timers as ISO 8601")
                 .separator()
                 .item(&radio_menu.submenu)
                 .item(
@@ -148,6 +173,8 @@ fn main() {
                         .check("heading-show::elapsed", "Elapsed time")
                         .build()?,
                 )
+                .item(&time_policy_menu.submenu)
+                .item(&locale_menu.submenu)
                 .build()?;
 
             app.manage(GlobalContextMenu(menu));
@@ -167,6 +194,10 @@ fn main() {
                     )
                     .unwrap();
                     println!("deleting all timers");
+                } else if event.id() == "export-iso" {
+                    app.emit("contextmenu::export-iso", String::from("exported timers"))
+                        .unwrap();
+                    println!("exporting timers");
                 } else if let Some(option) = event.id().0.strip_prefix("heading-show::") {
                     let menu_item = get_nested_menu_item(menu, event.id().0.as_str()).unwrap();
                     let menu_item = menu_item.as_check_menuitem_unchecked();
@@ -188,6 +219,7 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
             alert_window,
+            warn_window,
             contextmenu,
             set_contextmenu_checkitem
         ])
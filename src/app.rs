@@ -8,9 +8,11 @@ use web_sys::{
 };
 
 use crate::{
-    commands::{listen_event, popup_contextmenu, set_contextmenu_checkitem},
+    commands::{copy_to_clipboard, listen_event, popup_contextmenu, set_contextmenu_checkitem},
     contexts::{FullscreenElement, Icons, TimerList},
+    interpreter,
     pages::HomePage,
+    time::{locale::Locale, meridiem::MeridiemPolicy},
     timer::serialize,
 };
 
@@ -45,6 +47,9 @@ pub fn App() -> impl IntoView {
 
     listen_event("contextmenu::add-timer", move |_| timers.push_new());
     listen_event("contextmenu::delete-all", move |_| timers.clear());
+    listen_event("contextmenu::export-iso", move |_| {
+        spawn_local(copy_to_clipboard(serialize::export_iso(timers)));
+    });
 
     contextmenu_local_storage_sync();
 
@@ -142,6 +147,8 @@ fn contextmenu_local_storage_sync() {
     set_if_empty("heading-show::end-time", "true");
     set_if_empty("heading-show::elapsed", "false");
     set_if_empty("timer-face", "blur");
+    set_if_empty("time-policy", MeridiemPolicy::default().name());
+    set_if_empty("locale", Locale::default().name());
 
     let (timer_card, set_timer_card, _) =
         use_local_storage::<String, FromToStringCodec>("timer-face");
@@ -151,6 +158,23 @@ fn contextmenu_local_storage_sync() {
     });
     create_effect(move |_| set_body_attribute("data-timer-face-appearance", &timer_card.get()));
 
+    let (time_policy, set_time_policy, _) =
+        use_local_storage::<MeridiemPolicy, FromToStringCodec>("time-policy");
+
+    listen_event("contextmenu::time-policy", move |ev| {
+        match ev.payload.parse() {
+            Ok(policy) => set_time_policy(policy),
+            Err(e) => log::error!("invalid time policy emitted: {e}"),
+        }
+    });
+
+    let (locale, set_locale, _) = use_local_storage::<Locale, FromToStringCodec>("locale");
+
+    listen_event("contextmenu::locale", move |ev| match ev.payload.parse() {
+        Ok(new_locale) => set_locale(new_locale),
+        Err(e) => log::error!("invalid locale emitted: {e}"),
+    });
+
     let (heading_title, set_heading_title, _) =
         use_local_storage::<bool, FromToStringCodec>("heading-show::title");
     let (heading_end_time, set_heading_end_time, _) =
@@ -177,6 +201,18 @@ fn contextmenu_local_storage_sync() {
             set_contextmenu_checkitem("timer-face::blur", selected_appearance == 2),
         )
         .await;
+
+        let time_policy = time_policy.get_untracked();
+        for policy in MeridiemPolicy::ALL {
+            let path = format!("time-policy::{policy}");
+            set_contextmenu_checkitem(&path, policy == time_policy).await;
+        }
+
+        let locale = locale.get_untracked();
+        for other in Locale::ALL {
+            let path = format!("locale::{other}");
+            set_contextmenu_checkitem(&path, other == locale).await;
+        }
     });
 
     listen_event("contextmenu::heading-show", move |ev| {
@@ -208,7 +244,18 @@ fn store_timers(timers: TimerList) -> Option<()> {
 /// Returns `None` if local storage cannot be accessed or the item cannot be parsed.
 fn retrieve_timers() -> Option<TimerList> {
     let timers_string = get_setting("timers")?;
-    serialize::parse_timer_json(&timers_string)
+    let options = interpreter::Options {
+        meridiem_policy: get_setting("time-policy")
+            .and_then(|policy| policy.parse().ok())
+            .unwrap_or_default(),
+        locale: get_setting("locale")
+            .and_then(|locale| locale.parse().ok())
+            .unwrap_or_default(),
+    };
+    let presets = get_setting("presets")
+        .and_then(|presets| presets.parse().ok())
+        .unwrap_or_default();
+    serialize::parse_timer_json(&timers_string, options, &presets)
 }
 
 fn set_if_empty(key: &str, value: &str) -> Option<()> {
@@ -9,6 +9,9 @@ extern "C" {
 
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
     fn listen(event: &str, handler: &Function) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText, catch)]
+    async fn write_text(text: &str) -> Result<JsValue, JsValue>;
 }
 
 /// Requests user attention.
@@ -17,6 +20,21 @@ pub async fn alert_window() {
     invoke("alert_window", JsValue::UNDEFINED).await;
 }
 
+/// Requests user attention less urgently than [`alert_window`], for warnings
+/// before a timer finishes.
+pub async fn warn_window() {
+    log::info!("timer warning: requesting user attention");
+    invoke("warn_window", JsValue::UNDEFINED).await;
+}
+
+/// Copies `text` to the clipboard.
+pub async fn copy_to_clipboard(text: String) {
+    log::info!("copying {} bytes to the clipboard", text.len());
+    if let Err(e) = write_text(&text).await {
+        log::error!("failed to copy to the clipboard: {e:?}");
+    }
+}
+
 pub async fn popup_contextmenu() {
     log::info!("opened contextmenu");
     invoke("contextmenu", JsValue::UNDEFINED).await;
@@ -30,24 +30,30 @@ pub fn DurationDisplay(duration: impl Fn() -> Duration + Copy + 'static) -> impl
     let mins =
         create_memo(move |_| rounded_duration().whole_minutes() as u64 % units::MINS_IN_HOUR);
     let hours = create_memo(move |_| rounded_duration().whole_hours() as u64 % units::HOURS_IN_DAY);
-    let days = create_memo(move |_| rounded_duration().whole_days());
+    let days = create_memo(move |_| rounded_duration().whole_days() as u64 % units::DAYS_IN_WEEK);
+    let weeks = create_memo(move |_| rounded_duration().whole_weeks());
 
     mview! {
         span.com-duration {
             Show when={show_negative} {
                 span.negative { "-" }
             }
-            Show when=[days() != 0] {
+            Show when=[weeks() != 0] {
+                span.value { {weeks} }
+                span.unit { "w" }
+                " "
+            }
+            Show when=[days() != 0 || weeks() != 0] {
                 span.value { {days} }
                 span.unit { "d" }
                 " "
             }
-            Show when=[hours() != 0 || days() != 0] {
+            Show when=[hours() != 0 || days() != 0 || weeks() != 0] {
                 span.value { {hours} }
                 span.unit { "h" }
                 " "
             }
-            Show when=[mins() != 0 || hours() != 0 || days() != 0] {
+            Show when=[mins() != 0 || hours() != 0 || days() != 0 || weeks() != 0] {
                 span.value { {mins} }
                 span.unit { "m" }
                 " "
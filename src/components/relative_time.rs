@@ -2,7 +2,7 @@ use leptos::*;
 use leptos_mview::mview;
 use time::{format_description::FormatItem, macros::format_description, OffsetDateTime};
 
-use crate::time::relative;
+use crate::time::{relative, zone::Zone};
 
 const WEEKDAY_FORMAT: &[FormatItem<'_>] = format_description!("[weekday repr:short]");
 const FULL_DATE_FORMAT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
@@ -11,20 +11,32 @@ const TIME_FORMAT: &[FormatItem<'_>] =
 
 /// Displays a time (and date when needed) relative to now.
 ///
+/// If a `zone` is given that is at a different offset to the local one, the
+/// time in that zone is also shown after it, like "3:30 pm (9:30 am
+/// America/New_York)".
+///
 /// Only updates when `time` is updated.
 ///
 /// Renders an empty span if `None` is provided.
 #[component]
-pub fn RelativeTime(#[prop(into)] time: MaybeSignal<Option<OffsetDateTime>>) -> impl IntoView {
+pub fn RelativeTime(
+    #[prop(into)] time: MaybeSignal<Option<OffsetDateTime>>,
+    #[prop(optional, into)] zone: MaybeSignal<Option<Zone>>,
+) -> impl IntoView {
     let string = create_memo(move |_| {
         // ignore `None`
         if time().is_none() {
             return String::new();
         };
-        let time = time().expect("`None` should have caused early return");
+        let now = relative::now();
+        // compare dates in the same offset, otherwise the day can be wrong
+        // near midnight
+        let time = time()
+            .expect("`None` should have caused early return")
+            .to_offset(now.offset());
 
         // display a date if the target is on a different day.
-        let current_day = relative::now().date();
+        let current_day = now.date();
         let target_day = time.date();
         let days_between = (target_day - current_day).whole_days();
 
@@ -42,7 +54,16 @@ pub fn RelativeTime(#[prop(into)] time: MaybeSignal<Option<OffsetDateTime>>) ->
 
         let end_time = time.format(TIME_FORMAT).unwrap();
 
-        format!("{display_date} {end_time}").trim().to_string()
+        // the other zone's time, only if it would be different
+        let zone_time = zone()
+            .map(|zone| (zone, zone.convert(time)))
+            .filter(|(_, zoned)| zoned.offset() != time.offset())
+            .map(|(zone, zoned)| format!("({} {zone})", zoned.format(TIME_FORMAT).unwrap()))
+            .unwrap_or_default();
+
+        format!("{display_date} {end_time} {zone_time}")
+            .trim()
+            .to_string()
     });
 
     mview! { span.com-relative-time { {string} } }
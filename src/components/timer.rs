@@ -2,7 +2,7 @@ use leptos::*;
 use leptos_mview::mview;
 
 use leptos_use::{storage::use_local_storage, utils::FromToStringCodec};
-use std::time::Duration as StdDuration;
+use std::{ops::Range, time::Duration as StdDuration};
 use time::Duration;
 use wasm_bindgen::JsValue;
 
@@ -13,15 +13,24 @@ use crate::{
         RelativeTime,
     },
     contexts::TimerList,
+    interpreter::{self, Presets},
     reactive,
-    time::relative,
+    time::{locale::Locale, meridiem::MeridiemPolicy, relative},
     timer::MultiTimer,
 };
 
+/// The number of segments shown in the preview under the input.
+const PREVIEW_SEGMENTS: usize = 3;
+
 /// Provides controls and display for a [`Timer`].
 #[component]
 pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
-    let (error_message, set_error_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<interpreter::Error>);
+    let error_message = move || error().map(|e| e.to_string());
+    // cleared when the input changes, as the span would no longer line up
+    let error_span = RwSignal::new(None::<Range<usize>>);
+    // every error in the input, listed under it when there is more than one
+    let diagnostics = RwSignal::new(Vec::<interpreter::Error>::new());
 
     let update_time_elapsed = Trigger::new();
     let time_elapsed = Memo::new(move |_| {
@@ -67,6 +76,7 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
     });
 
     let peek = RwSignal::new(timer.peek());
+    let current = RwSignal::new(timer.current());
 
     let component = NodeRef::<html::Div>::new();
     let duration_display = NodeRef::<html::Div>::new();
@@ -75,17 +85,98 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
         if finished() {
             timer.next();
             peek.set(timer.peek());
-            flash(duration_display)
+            current.set(timer.current());
+            flash(duration_display, "var(--clr-red-400)")
         }
     });
 
-    let start = move || match timer.start() {
-        Ok(_) => {
-            set_error_message(None);
-            peek.set(timer.peek());
+    // the number of the current segment's warnings that have been reached,
+    // which are sorted from longest to shortest
+    let warnings_reached = Memo::new(move |_| {
+        let remaining = time_remaining()?;
+        current.with(|s| {
+            s.as_ref()
+                .map(|s| s.warnings().take_while(|w| remaining <= *w).count())
+        })
+    });
+
+    Effect::new(move |prev: Option<Option<usize>>| {
+        let reached = warnings_reached();
+        if let (Some(Some(prev)), Some(reached)) = (prev, reached)
+            && reached > prev
+            && !finished.get_untracked()
+        {
+            // like when finishing, only alert close to the warning so that
+            // segments shorter than their warnings don't alert
+            let warning = current.with_untracked(|s| s.as_ref()?.warnings().nth(reached - 1));
+            let remaining = time_remaining.get_untracked();
+            if let Some((warning, remaining)) = warning.zip(remaining)
+                && warning - remaining < Duration::SECOND
+            {
+                spawn_local(commands::warn_window());
+                flash(duration_display, "var(--clr-orange-400)");
+            }
+        }
+        reached
+    });
+
+    // how inputs are read, set from the context menu
+    let (time_policy, _, _) = use_local_storage::<MeridiemPolicy, FromToStringCodec>("time-policy");
+    let (locale, _, _) = use_local_storage::<Locale, FromToStringCodec>("locale");
+    let options = Signal::derive(move || interpreter::Options {
+        meridiem_policy: time_policy(),
+        locale: locale(),
+    });
+    // named inputs, saved by entering an input like "pomo = 25m + 5m"
+    let (presets, set_presets, _) = use_local_storage::<Presets, FromToStringCodec>("presets");
+
+    let start = move || {
+        let options = options.get_untracked();
+        let mut new_presets = presets.get_untracked();
+        let definition = timer
+            .input()
+            .with_untracked(|input| new_presets.define(input, options));
+        let result = match definition {
+            Some(result) => result.map(|()| {
+                set_presets(new_presets);
+                timer.input().set(String::new());
+            }),
+            None => presets.with_untracked(|presets| {
+                let result = timer.start(options, presets);
+                // look for the rest of the errors too, so they can all be fixed at once
+                diagnostics.set(match result {
+                    Ok(()) => Vec::new(),
+                    Err(_) => timer.input().with_untracked(|input| {
+                        interpreter::diagnose_multi(input, options, presets)
+                    }),
+                });
+                result.map(|()| {
+                    peek.set(timer.peek());
+                    current.set(timer.current());
+                })
+            }),
+        };
+        match result {
+            Ok(()) => {
+                set_error(None);
+                error_span.set(None);
+            }
+            Err(e) => {
+                error_span.set(Some(e.span.clone()));
+                set_error(Some(e));
+            }
         }
-        Err(e) => {
-            set_error_message(Some(e.to_string()));
+    };
+
+    // replaces the misspelt part of the input with the suggestion and retries
+    let apply_suggestion = move || {
+        let fixed = error.with_untracked(|e| {
+            let e = e.as_ref()?;
+            timer.input().with_untracked(|input| e.apply_suggestion(input))
+        });
+        if let Some(fixed) = fixed {
+            timer.input().set(fixed);
+            start();
         }
     };
 
@@ -94,17 +185,118 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
 
     // sub-components //
 
+    let current_label = move || current().and_then(|s| s.label().map(str::to_string));
+    // show the label of the next segment, or its duration if it has no label
+    let next_name = move || {
+        peek().map(|s| s.label().map_or_else(|| s.to_string(), str::to_string))
+    };
+
     let next_time = move || {
         mview! {
+            Show when=[current_label().is_some() && timer.started()()] {
+                div.current-segment {
+                   span("now:")
+                   span({current_label})
+                }
+            }
             Show when=[peek().is_some() && timer.started()()] {
                 div.next-timer {
                    span("next:")
-                   span({peek().map(|s| s.to_string())})
+                   span({next_name})
                 }
             }
         }
     };
 
+    // shows what the input would do while it is being typed
+    let explanation = Memo::new(move |_| {
+        timer
+            .input()
+            .with(|input| {
+                presets.with(|presets| {
+                    interpreter::explain_multi(input, PREVIEW_SEGMENTS, options(), presets)
+                })
+            })
+            .ok()
+    });
+    let preview = move || {
+        let explanation = explanation()?;
+        let mut segments = explanation
+            .segments
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        match explanation.count {
+            Some(count) if count > explanation.segments.len() as u64 => {
+                segments.push_str(&format!(", … ({count} timers)"));
+            }
+            Some(_) => {}
+            None => segments.push_str(", …"),
+        }
+
+        Some(mview! {
+            span.segments { {segments} }
+            [explanation.total.map(|total| mview! {
+                " | "
+                span.total {
+                    DurationDisplay duration={move || total};
+                }
+                " | "
+                span.end {
+                    Icon icon="ph:timer-bold";
+                    " "
+                    RelativeTime time={explanation.end_time} zone={explanation.zone};
+                }
+            })]
+        })
+    };
+
+    // underlines the part of the input that caused the error, drawn behind
+    // the input with the same text so that it lines up
+    let input_highlight = move || {
+        let span = error_span()?;
+        let input = timer.input()();
+        let (before, error, after) = split_span(&input, span)?;
+        Some(mview! {
+            span({before.to_string()})
+            span.error-span({error.to_string()})
+            span({after.to_string()})
+        })
+    };
+
+    // each error with the part of the input that caused it, if any
+    let diagnostic_list = move || {
+        let input = timer.input().get_untracked();
+        diagnostics.with(|errors| {
+            errors
+                .iter()
+                .map(|e| {
+                    let part = input
+                        .get(e.span.clone())
+                        .filter(|part| !part.is_empty())
+                        .map(|part| format!("\"{part}\": "));
+                    let message = e.to_string();
+                    mview! {
+                        li { {part} {message} }
+                    }
+                })
+                .collect_view()
+        })
+    };
+
+    // only offered while the input is the one that caused the error
+    let suggestion_button = move || {
+        error_span()?;
+        let suggestion = error()?.suggestion()?;
+        Some(mview! {
+            " "
+            button.suggestion.mix-btn-transp-neutral on:click={move |_| apply_suggestion()} {
+                "use \"" {suggestion} "\"?"
+            }
+        })
+    };
+
     // switch between resume and pause button
     let pause_button = move || {
         if timer.paused()() {
@@ -177,6 +369,9 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
             + Duration::MILLISECOND
     };
 
+    // the zone that the input ends on, to also show the end time in
+    let end_zone = Signal::derive(move || explanation().and_then(|e| e.zone));
+
     let (show_heading_title, _, _) =
         use_local_storage::<bool, FromToStringCodec>("heading-show::title");
     let (show_heading_end_time, _, _) =
@@ -201,7 +396,7 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
             span.end {
                 Icon icon="ph:timer-bold";
                 " "
-                RelativeTime time={end_time};
+                RelativeTime time={end_time} zone={end_zone};
             }
         }
         .into_view(),
@@ -214,7 +409,7 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
         .into_view(),
         // this will only be shown with the title, if it exists
         mview! {
-            span.error { {error_message} }
+            span.error { {error_message} {suggestion_button} }
         }
         .into_view(),
     ];
@@ -266,16 +461,25 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
                             }.into_view()
                         } else {
                             mview! {
-                                input
-                                    type="text"
-                                    // set old value when reset timer
-                                    prop:value={timer.input()}
-                                    on:input={move |ev| timer.input().set(event_target_value(&ev))}
-                                    on:keydown={move |ev| {
-                                        if ev.key() == "Enter" {
-                                            start();
-                                        }
-                                    }};
+                                div.input-wrapper {
+                                    input
+                                        type="text"
+                                        // set old value when reset timer
+                                        prop:value={timer.input()}
+                                        on:input={move |ev| {
+                                            error_span.set(None);
+                                            diagnostics.set(Vec::new());
+                                            timer.input().set(event_target_value(&ev));
+                                        }}
+                                        on:keydown={move |ev| {
+                                            if ev.key() == "Enter" {
+                                                start();
+                                            }
+                                        }};
+                                    div.input-highlight aria-hidden="true" {
+                                        {input_highlight}
+                                    }
+                                }
                             }.into_view()
                         }]
                     }
@@ -283,6 +487,13 @@ pub fn TimerDisplay(timer: MultiTimer) -> impl IntoView {
                     {next_time}
                 }
 
+                Show when=[!timer.started()()] {
+                    div.input-preview { {preview} }
+                }
+                Show when=[!timer.started()() && diagnostics.with(|d| d.len() > 1)] {
+                    ul.diagnostics { {diagnostic_list} }
+                }
+
                 div.controls { {controls} }
 
 
@@ -300,6 +511,17 @@ fn remove_self(timer: MultiTimer) {
     timers.remove_id(timer.id());
 }
 
+/// Splits the input into the parts before, inside and after `span`.
+///
+/// Returns `None` if the span is not inside the input.
+fn split_span(input: &str, span: Range<usize>) -> Option<(&str, &str, &str)> {
+    Some((
+        input.get(..span.start)?,
+        input.get(span.clone())?,
+        input.get(span.end..)?,
+    ))
+}
+
 /// Creates a JS object with one key-value pair.
 fn js_obj_1(key: &str, value: &str) -> js_sys::Object {
     let obj = js_sys::Object::new();
@@ -340,7 +562,8 @@ fn update_and_bump(duration: Duration, element: NodeRef<html::Div>, timer: Multi
     };
 }
 
-fn flash(element: NodeRef<html::Div>) {
+/// Flashes the text of the element in `color`, which is a CSS color.
+fn flash(element: NodeRef<html::Div>, color: &str) {
     let Some(display) = element.get_untracked() else {
         return;
     };
@@ -349,13 +572,13 @@ fn flash(element: NodeRef<html::Div>) {
     anim_options.duration(&JsValue::from_f64(480.0));
     anim_options.easing("steps(5, end)");
 
-    // makes 3 red flashes whether it ends white or ends red
+    // makes 3 flashes whether it ends white or ends in the color
     let anim_up_keyframes: js_sys::Array = [
-        js_obj_1("color", "var(--clr-red-400)"),
+        js_obj_1("color", color),
         js_obj_1("color", "var(--clr-text)"),
-        js_obj_1("color", "var(--clr-red-400)"),
+        js_obj_1("color", color),
         js_obj_1("color", "var(--clr-text)"),
-        js_obj_1("color", "var(--clr-red-400)"),
+        js_obj_1("color", color),
         js_obj_1("color", "var(--clr-text)"),
     ]
     .into_iter()
@@ -1,42 +1,94 @@
 mod eval;
+mod format;
 mod lexer;
 mod multi;
+mod natural;
 mod parser;
+mod plan;
+mod presets;
+mod random;
+mod suggest;
+
+use std::ops::Range;
 
 use thiserror::Error;
-use time::Duration;
+use time::{Duration, OffsetDateTime};
+
+use crate::time::{locale::Locale, meridiem::MeridiemPolicy, relative, zone::Zone};
 
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T, E = Error> = std::result::Result<T, E>;
 
-pub use multi::{interpret_multi, InputIter};
+pub use format::format_duration;
+pub use multi::{
+    diagnose_multi, explain_multi, format_multi, interpret_multi, interpret_multi_at,
+    interpret_multi_with, interpret_multi_with_presets, Explanation, Segment,
+};
+pub use plan::Plan;
+pub use presets::Presets;
 
 /// Tries to parse a user inputted string as a duration.
 ///
-/// There are 2 main formats:
+/// There are 3 main formats:
 /// - A duration, specified with units like "1h 30m".
-///     - The units accepted are days, hours, minutes, seconds and
-///       milliseconds. Several different ways of writing each are accepted
-///       (e.g. "h", "hrs", "hours").
+///     - The units accepted are years, months, weeks, days, hours, minutes,
+///       seconds and milliseconds. Several different ways of writing each are
+///       accepted (e.g. "h", "hrs", "hours").
+///     - Whole months and years follow the calendar, so "1mo" lasts until the
+///       same date next month.
 ///     - If no units are given, minutes is assumed.
 ///     - If the string ends in a number with no unit, it is assumed to be one
 ///       unit smaller than the previous (e.g. "2m 30" is the same as "2m 30s").
-///     - Decimals are accepted, like "3.5h".
+///     - Decimals and fractions are accepted, like "3.5h", "1/2h" or "1 1/2
+///       hours".
+///     - Numbers and fractions can be written as words, like "an hour and a
+///       half" or "twenty five minutes".
 /// - A specific time, like "5:30pm". Finds the duration until the next
 ///   occurrence of the specified time.
 ///     - If "am" or "pm" is added, the duration until the next occurrence of
 ///       that time is returned.
 ///     - If no "am" or "pm" is added, it will be interpreted as the closest one
 ///       (e.g. at 2pm, "3:30" is the same as "3:30pm" and "1:30" is the same
-///       as "1:30am").
+///       as "1:30am"). This can be changed with [`Options`], see
+///       [`MeridiemPolicy`].
 ///     - A no-meridiem time with only the hour time can be inputted by adding
 ///       a ":" (e.g. "3" is interpreted as 3 minutes while "3:" is interpreted
 ///       as 3 am/pm, whichever is closest).
+///     - Hours of 0 or 13-23 are read as 24-hour time (e.g. "17:30"), and
+///       can use "h" as the separator (e.g. "17h30").
+///     - Times can also be written like "half past 3", "quarter to 5",
+///       "noon" or "midnight".
+///     - Starting with "at" always uses 24-hour time, and allows "h" as the
+///       separator (e.g. "at 5:30" is 5:30am and "at 17h30" is 5:30pm).
+///     - A day can be added before or after the time, as "today", "tomorrow",
+///       a weekday like "fri", or an ISO date like "2026-12-24". Times on a
+///       day use 24-hour time if no "am" or "pm" is given, and a day by
+///       itself is midnight at the start of that day.
+///     - A time zone can be added at the end, as an IANA name like
+///       "Europe/Berlin" or an offset like "UTC+2" (e.g. "9:30am
+///       Europe/Berlin"). The time and day are then in that zone instead of
+///       the local one.
+///     - A time can be offset by a duration, like "10m before 3pm", "20m
+///       after 9am", "3pm - 10m" or "9am + 20m". This is the next time that
+///       is that far from the time, so at 9:10am "20m after 9am" is in 10
+///       minutes. In [`interpret_multi`], only "before" and "after" can be
+///       used, as "+" and "-" join timers.
+/// - ISO 8601, as the whole input.
+///     - A duration like "PT1H30M" or "P2DT3H". Whole years and months follow
+///       the calendar, like "1mo".
+///     - A date and time like "2026-10-16T17:00:00+02:00", giving the
+///       duration until then. Without an offset, it is in the local time
+///       zone.
+///
+/// Units, meridiems and number words can also be written in another language,
+/// with the decimal separator of that language (e.g. "1,5 Std" in German), by
+/// setting the locale in [`Options`]. See [`Locale`].
 ///
 /// # Errors
 /// Errors if the input does not match any of the above formats.
 ///
 /// The error reason will try to be given, however it may be inconsistent
-/// and change if the implementation is modified.
+/// and change if the implementation is modified. The error also has the byte
+/// range of the input that caused it.
 ///
 /// # Examples
 /// ```rust
@@ -50,18 +102,188 @@ pub use multi::{interpret_multi, InputIter};
 /// );
 /// ```
 pub fn interpret_single(input: &str) -> Result<Duration> {
+    interpret_single_with(input, Options::default())
+}
+
+/// Interprets `input` like [`interpret_single`], using `options` instead of
+/// the defaults.
+///
+/// # Errors
+/// Errors if the input is not valid, see [`interpret_single`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::{
+///     interpreter::{interpret_single, interpret_single_with, Options},
+///     time::meridiem::MeridiemPolicy,
+/// };
+/// use time::Duration;
+///
+/// let options = Options {
+///     meridiem_policy: MeridiemPolicy::Strict24h,
+///     ..Options::default()
+/// };
+/// let duration = interpret_single_with("3:30", options).unwrap();
+/// let expected = interpret_single("3:30am").unwrap();
+/// // both are relative to now, which moves slightly between them
+/// assert!((duration - expected).abs() < Duration::SECOND);
+/// ```
+pub fn interpret_single_with(input: &str, options: Options) -> Result<Duration> {
+    interpret_single_at(input, Reference::current(), options)
+}
+
+/// Interprets `input` like [`interpret_single_with`], but relative to
+/// `reference` instead of the current time and local time zone.
+///
+/// This always gives the same result for the same input, reference and
+/// options.
+///
+/// # Errors
+/// Errors if the input is not valid, see [`interpret_single`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::{
+///     interpreter::{interpret_single_at, Options, Reference},
+///     time::zone::{parse_zone, Zone},
+/// };
+/// use time::{ext::NumericalDuration, macros::{datetime, offset}};
+///
+/// let options = Options::default();
+/// let reference = Reference {
+///     now: datetime!(2026-10-16 14:00 UTC),
+///     zone: Zone::Fixed(offset!(UTC)),
+/// };
+/// assert_eq!(interpret_single_at("5pm", reference, options).unwrap(), 3.hours());
+/// assert_eq!(
+///     interpret_single_at("tomorrow 9am", reference, options).unwrap(),
+///     19.hours()
+/// );
+///
+/// let reference = Reference {
+///     zone: parse_zone("Europe/Berlin").unwrap(),
+///     ..reference
+/// };
+/// assert_eq!(interpret_single_at("5pm", reference, options).unwrap(), 1.hours());
+/// ```
+pub fn interpret_single_at(
+    input: &str,
+    reference: Reference,
+    options: Options,
+) -> Result<Duration> {
     log::debug!("parsing input {input}");
 
-    let groups = lexer::lex(input)?;
-    let tokens = parser::parse(groups)?;
+    let vocabulary = options.locale.vocabulary();
+    let groups = lexer::lex(input, vocabulary)?;
+    let groups = natural::rewrite(groups, vocabulary)?;
+    let tokens = parser::parse(groups, vocabulary)?;
     log::trace!("successfully mapped to parsed tokens");
 
-    eval::eval(&tokens)
+    eval::eval(&tokens, reference, options)
+}
+
+/// Whether `input` is a specific time like "5pm", rather than a length of
+/// time like "25m", so its duration depends on when it is interpreted.
+///
+/// Invalid inputs are not times.
+fn is_time(input: &str, options: Options) -> bool {
+    let vocabulary = options.locale.vocabulary();
+    lexer::lex(input, vocabulary)
+        .and_then(|groups| natural::rewrite(groups, vocabulary))
+        .and_then(|groups| parser::parse(groups, vocabulary))
+        .is_ok_and(|tokens| eval::is_time(&tokens))
+}
+
+/// The moment and time zone that an input is interpreted at.
+///
+/// Specific times like "5pm" are found relative to this, as are calendar
+/// units like "1mo".
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Reference {
+    /// The time that durations are measured from.
+    pub now: OffsetDateTime,
+    /// The time zone that times are in, unless another one is given.
+    pub zone: Zone,
+}
+
+impl Reference {
+    /// The current time in the local time zone.
+    ///
+    /// # Panics
+    /// Panics if the local time cannot be determined.
+    pub fn current() -> Self {
+        Self {
+            now: relative::now(),
+            zone: Zone::local(),
+        }
+    }
+}
+
+/// Settings for how inputs are interpreted.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Options {
+    /// How times without am or pm, like "3:30", are read.
+    pub meridiem_policy: MeridiemPolicy,
+    /// The language that units, meridiems and number words are written in,
+    /// and the decimal separator used.
+    pub locale: Locale,
+}
+
+/// The error type for [`interpret_single`] and [`interpret_multi`].
+#[derive(Debug, PartialEq, Clone, Error)]
+#[error("{kind}")]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// The byte range of the input that caused the error.
+    ///
+    /// This is empty if something is missing, like the end of the input for
+    /// "30d +".
+    pub span: Range<usize>,
+}
+
+impl Error {
+    pub const fn new(kind: ErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    /// The replacement for the span suggested by this error, if any.
+    pub const fn suggestion(&self) -> Option<&'static str> {
+        match self.kind {
+            ErrorKind::MisspeltUnit { suggestion, .. } => Some(suggestion),
+            _ => None,
+        }
+    }
+
+    /// Replaces the span of `input` with the suggestion from this error.
+    ///
+    /// `input` should be the input that caused this error. Returns `None` if
+    /// there is no suggestion or the span is not inside `input`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use minti_ui::interpreter::interpret_single;
+    /// let error = interpret_single("3 hrs 20 mni").unwrap_err();
+    /// assert_eq!(error.apply_suggestion("3 hrs 20 mni").unwrap(), "3 hrs 20 min");
+    /// ```
+    pub fn apply_suggestion(&self, input: &str) -> Option<String> {
+        let suggestion = self.suggestion()?;
+        let before = input.get(..self.span.start)?;
+        let after = input.get(self.span.end..)?;
+        Some(format!("{before}{suggestion}{after}"))
+    }
+
+    /// Moves the span `offset` bytes later, for errors from a slice of a
+    /// larger input.
+    #[must_use]
+    pub(crate) const fn offset(mut self, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
 }
 
-/// The error type for `parse::parse_input`.
+/// The reason for an [`Error`].
 #[derive(Debug, PartialEq, Clone, Error)]
-pub enum Error {
+pub enum ErrorKind {
     #[error("Unknown number")]
     NaN,
     #[error("Invalid character \"{0}\"")]
@@ -70,6 +292,11 @@ pub enum Error {
     InvalidNumber(String),
     #[error("Invalid unit \"{0}\"")]
     InvalidUnit(String),
+    #[error("Invalid unit \"{unit}\", did you mean \"{suggestion}\"?")]
+    MisspeltUnit {
+        unit: String,
+        suggestion: &'static str,
+    },
     #[error("Value \"{0}\" is less than a millisecond")]
     SmallerThanMilli(f64),
     #[error("Multiple formats detected")]
@@ -88,10 +315,67 @@ pub enum Error {
     InvalidOp(String),
     #[error("Invalid value position for {0}")]
     InvalidValue(String),
+    #[error("Incomplete phrase around \"{0}\"")]
+    IncompletePhrase(String),
+    #[error("Invalid date \"{0}\"")]
+    InvalidDate(String),
+    #[error("Unknown time zone \"{0}\"")]
+    InvalidZone(String),
+    #[error("Time is in the past")]
+    InPast,
+    #[error("An offset needs a duration on one side and a time on the other")]
+    InvalidOffset,
+    #[error("Cannot subtract more time than there is")]
+    NegativeDuration,
+    #[error("Subtraction leaves no timers remaining")]
+    EmptySequence,
+    #[error("Cannot subtract from a sequence that repeats forever")]
+    UnboundedSubtract,
+    #[error("\"until\" needs a single time after it")]
+    InvalidDeadline,
+    #[error("Cannot tell when a group repeated until a time would start")]
+    UnknownStart,
+    #[error("Timers that take no time never reach the time given")]
+    UnreachableDeadline,
+    #[error("\"@\" needs a duration before the end of the timer")]
+    InvalidWarning,
+    #[error("Unknown preset \"{0}\"")]
+    UnknownPreset(String),
+    #[error("Preset \"{0}\" uses itself")]
+    PresetCycle(String),
+    #[error("\"{0}\" already has a meaning, so cannot be a preset name")]
+    InvalidPresetName(String),
+    #[error(
+        "Random durations are written like \"rand(3m, 7m)\" or \"~5m±1m\", and cannot be negative"
+    )]
+    InvalidRandom,
+    #[error("The length of a random duration is not known until it starts")]
+    RandomLength,
+    #[error("Duration is too long")]
+    TooLong,
     #[error("{0}")]
     Other(String),
 }
 
+impl ErrorKind {
+    /// Attaches the range of the input that caused this error.
+    pub(crate) const fn at(self, span: Range<usize>) -> Error {
+        Error::new(self, span)
+    }
+
+    /// Creates an error for an unknown `unit`, suggesting the closest of
+    /// `tokens` if it looks like a typo.
+    pub(crate) fn invalid_unit(unit: &str, tokens: impl IntoIterator<Item = &'static str>) -> Self {
+        match suggest::closest(unit, tokens) {
+            Some(suggestion) => Self::MisspeltUnit {
+                unit: unit.to_string(),
+                suggestion,
+            },
+            None => Self::InvalidUnit(unit.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,6 +412,37 @@ mod tests {
             )
         }
 
+        #[test]
+        fn calendar_units() {
+            use crate::time::relative::duration_of_months;
+
+            assert_eq!(interpret_single("2w").unwrap(), 14.days());
+            assert_eq!(interpret_single("1 week 2d").unwrap(), 9.days());
+            assert_eq!(
+                interpret_single("1mo").unwrap().whole_seconds(),
+                duration_of_months(1).unwrap().whole_seconds()
+            );
+            assert_eq!(
+                interpret_single("1y 2mo 3d").unwrap().whole_seconds(),
+                (duration_of_months(14).unwrap() + 3.days()).whole_seconds()
+            );
+            assert_eq!(
+                interpret_single("1.5 months").unwrap().whole_seconds(),
+                (duration_of_months(1).unwrap() + 15.218_437_5.days()).whole_seconds()
+            );
+
+            // past the year 9999
+            assert_eq!(interpret_single("10000y"), Err(ErrorKind::TooLong.at(5..6)));
+            assert_eq!(
+                interpret_single("99999999999999999999mo"),
+                Err(ErrorKind::TooLong.at(20..22))
+            );
+            assert_eq!(
+                interpret_single("1000000000000000000y"),
+                Err(ErrorKind::TooLong.at(19..20))
+            );
+        }
+
         #[test]
         fn trailing_number() {
             assert_eq!(interpret_single("3h4").unwrap(), 3.hours() + 4.minutes());
@@ -139,29 +454,321 @@ mod tests {
         }
     }
 
+    mod natural {
+        use super::*;
+
+        #[test]
+        fn number_words() {
+            assert_eq!(interpret_single("five minutes").unwrap(), 5.minutes());
+            assert_eq!(interpret_single("an hour").unwrap(), 1.hours());
+            assert_eq!(
+                interpret_single("twenty five seconds").unwrap(),
+                25.seconds()
+            );
+            assert_eq!(
+                interpret_single("twenty-five seconds").unwrap(),
+                25.seconds()
+            );
+            assert_eq!(
+                interpret_single("1 hour and 30 minutes").unwrap(),
+                1.hours() + 30.minutes()
+            );
+        }
+
+        #[test]
+        fn fractions() {
+            assert_eq!(interpret_single("an hour and a half").unwrap(), 1.5.hours());
+            assert_eq!(
+                interpret_single("two and a half hours").unwrap(),
+                2.5.hours()
+            );
+            assert_eq!(interpret_single("half an hour").unwrap(), 30.minutes());
+            assert_eq!(
+                interpret_single("three quarters of an hour").unwrap(),
+                45.minutes()
+            );
+            assert_eq!(interpret_single("half hour").unwrap(), 30.minutes());
+        }
+    }
+
+    mod locales {
+        use super::*;
+
+        fn with(input: &str, locale: Locale) -> Result<Duration> {
+            let options = Options {
+                locale,
+                ..Options::default()
+            };
+            interpret_single_with(input, options)
+        }
+
+        #[test]
+        fn german() {
+            let de = Locale::German;
+            assert_eq!(with("1,5 Std", de), Ok(90.minutes()));
+            assert_eq!(with("1.5 std", de), Ok(90.minutes()));
+            assert_eq!(with("30 Min.", de), Ok(30.minutes()));
+            assert_eq!(with("2 Tage 3 Stunden", de), Ok(2.days() + 3.hours()));
+            assert_eq!(with("fünf Minuten", de), Ok(5.minutes()));
+            assert_eq!(with("eine halbe Stunde", de), Ok(30.minutes()));
+            assert_eq!(
+                with("2 Stunden und 30 Minuten", de),
+                Ok(2.hours() + 30.minutes())
+            );
+            assert!(with("2 hours", de).is_err());
+        }
+
+        #[test]
+        fn french() {
+            let fr = Locale::French;
+            assert_eq!(with("1,5 h", fr), Ok(90.minutes()));
+            assert_eq!(with("3 jours", fr), Ok(3.days()));
+            assert_eq!(with("vingt cinq minutes", fr), Ok(25.minutes()));
+            assert_eq!(with("une demi heure", fr), Ok(30.minutes()));
+            assert_eq!(with("une heure et demie", fr), Ok(90.minutes()));
+        }
+
+        #[test]
+        fn english() {
+            assert_eq!(
+                with("1,5h", Locale::English),
+                Err(ErrorKind::InvalidCharacter(',').at(1..2))
+            );
+            assert_eq!(interpret_single("5 min."), Ok(5.minutes()));
+        }
+    }
+
     mod times {
-        use crate::time::relative::duration_until_time;
-        use time::Time;
+        use time::macros::{datetime, offset};
 
         use super::*;
+        use crate::time::zone::parse_zone;
+
+        /// A Friday afternoon, in UTC.
+        const FRIDAY: Reference = Reference {
+            now: datetime!(2026-10-16 14:00 UTC),
+            zone: Zone::Fixed(offset!(UTC)),
+        };
+
+        /// Interprets `input` at [`FRIDAY`].
+        fn at(input: &str) -> Result<Duration> {
+            interpret_single_at(input, FRIDAY, Options::default())
+        }
 
         #[test]
         fn specific_12h_time() {
+            assert_eq!(at("3pm"), Ok(1.hours()));
+            assert_eq!(at("3:12pm"), Ok(1.hours() + 12.minutes()));
+            assert_eq!(
+                at("5:12:30 am"),
+                Ok(15.hours() + 12.minutes() + 30.seconds())
+            );
+        }
+
+        #[test]
+        fn natural_times() {
+            assert_eq!(at("half past 3"), Ok(1.hours() + 30.minutes()));
+            assert_eq!(at("quarter to 5"), Ok(2.hours() + 45.minutes()));
+            assert_eq!(at("twenty five to 1"), Ok(10.hours() + 35.minutes()));
+            assert_eq!(at("quarter past 3pm"), Ok(1.hours() + 15.minutes()));
+            assert_eq!(at("noon"), Ok(22.hours()));
+            assert_eq!(at("midnight"), Ok(10.hours()));
+        }
+
+        #[test]
+        fn specific_day() {
+            assert_eq!(at("tomorrow 9am"), Ok(19.hours()));
+            assert_eq!(at("fri 5pm"), Ok(3.hours()));
+            assert_eq!(at("5pm friday"), Ok(3.hours()));
+            // already passed today, so next week
+            assert_eq!(at("fri 9am"), Ok(7.days() - 5.hours()));
+            assert_eq!(
+                at("2099-12-24 18:00"),
+                Ok(datetime!(2099-12-24 18:00 UTC) - FRIDAY.now)
+            );
+            assert_eq!(at("tmr"), Ok(10.hours()));
+
+            assert_eq!(at("today 1pm"), Err(ErrorKind::InPast.at(0..9)));
+            assert_eq!(at("2020-01-01 5pm"), Err(ErrorKind::InPast.at(0..14)));
+            assert_eq!(
+                at("2099-02-30 5pm"),
+                Err(ErrorKind::InvalidDate("2099-02-30".to_string()).at(0..10))
+            );
+        }
+
+        #[test]
+        fn specific_24h_time() {
+            assert_eq!(at("17:30"), Ok(3.hours() + 30.minutes()));
+            assert_eq!(at("13:0:0"), Ok(23.hours()));
+            assert_eq!(at("0:15"), Ok(10.hours() + 15.minutes()));
+            assert_eq!(at("at 5:30"), Ok(15.hours() + 30.minutes()));
+            assert_eq!(at("at 17h30"), Ok(3.hours() + 30.minutes()));
+            assert_eq!(at("17h30"), Ok(3.hours() + 30.minutes()));
+            assert_eq!(at("tomorrow 13h05"), Ok(1.days() - 55.minutes()));
+            // smaller hours could be am or pm, so are still durations
+            assert_eq!(at("3h4"), Ok(3.hours() + 4.minutes()));
+            assert_eq!(at("17h30m"), Ok(17.hours() + 30.minutes()));
+            assert_eq!(at("at 9"), Ok(19.hours()));
+        }
+
+        #[test]
+        fn time_zones() {
+            // 4pm in Berlin
+            assert_eq!(at("9:30am Europe/Berlin"), Ok(17.hours() + 30.minutes()));
+            // 7:30pm at +5:30
+            assert_eq!(at("at 17:30 utc+5:30"), Ok(22.hours()));
+            // 10am in New York
+            assert_eq!(at("tomorrow 9am America/New_York"), Ok(23.hours()));
+            assert_eq!(at("3pm UTC"), Ok(1.hours()));
+            assert_eq!(at("15:00 gmt+0"), Ok(1.hours()));
+
+            // the clocks go back overnight in Berlin
+            let reference = Reference {
+                now: datetime!(2026-10-24 22:00 +2),
+                zone: parse_zone("Europe/Berlin").unwrap(),
+            };
+            let options = Options::default();
+            assert_eq!(
+                interpret_single_at("7am", reference, options),
+                Ok(10.hours())
+            );
+            assert_eq!(
+                interpret_single_at("7am utc+2", reference, options),
+                Ok(9.hours())
+            );
+
+            assert_eq!(
+                at("3pm Europe/Berln"),
+                Err(ErrorKind::InvalidZone("europe/berln".to_string()).at(4..16))
+            );
+            assert_eq!(at("utc"), Err(ErrorKind::Empty.at(0..0)));
+            assert!(at("5m utc").is_err());
+            assert!(at("utc 3pm").is_err());
+        }
+
+        #[test]
+        fn offsets() {
+            assert_eq!(at("10m before 3pm"), Ok(50.minutes()));
+            assert_eq!(at("20m after 9am"), Ok(19.hours() + 20.minutes()));
+            assert_eq!(at("3pm - 10m"), Ok(50.minutes()));
+            assert_eq!(at("9am + 20m"), Ok(19.hours() + 20.minutes()));
+            // 1pm has passed, but 2 hours after it hasn't
+            assert_eq!(at("2h after 1pm"), Ok(1.hours()));
+            assert_eq!(at("1h before tomorrow 9am"), Ok(18.hours()));
+            assert_eq!(at("5m before 10m before 3pm"), Ok(45.minutes()));
+
+            assert_eq!(
+                at("10m before today 2pm"),
+                Err(ErrorKind::InPast.at(11..20))
+            );
             assert_eq!(
-                interpret_single("3pm").unwrap().whole_seconds(),
-                duration_until_time(Time::from_hms(3 + 12, 0, 0).unwrap()).whole_seconds()
+                at("5m before 10m"),
+                Err(ErrorKind::InvalidOffset.at(10..13))
             );
+            assert_eq!(at("before 3pm"), Err(ErrorKind::InvalidOffset.at(0..6)));
+            assert_eq!(at("3-4"), Err(ErrorKind::InvalidOffset.at(0..1)));
+        }
+
+        #[test]
+        fn meridiem_policies() {
+            let with = |input, meridiem_policy| {
+                let options = Options {
+                    meridiem_policy,
+                    ..Options::default()
+                };
+                interpret_single_at(input, FRIDAY, options)
+            };
+
+            let policy = MeridiemPolicy::Closest;
+            assert_eq!(with("3:30", policy), Ok(1.hours() + 30.minutes()));
+            assert_eq!(with("1:30", policy), Ok(11.hours() + 30.minutes()));
+            assert_eq!(with("today 3:30", policy), Err(ErrorKind::InPast.at(0..10)));
+
+            let policy = MeridiemPolicy::NextFuture;
+            assert_eq!(with("1:30", policy), Ok(11.hours() + 30.minutes()));
+            assert_eq!(with("today 3:30", policy), Ok(1.hours() + 30.minutes()));
+            assert_eq!(with("tomorrow 3:30", policy), Ok(13.hours() + 30.minutes()));
+
+            let policy = MeridiemPolicy::WorkingHours;
+            assert_eq!(with("1:30", policy), Ok(23.hours() + 30.minutes()));
+            assert_eq!(with("9:", policy), Ok(19.hours()));
+            assert_eq!(with("today 3:30", policy), Ok(1.hours() + 30.minutes()));
+            assert_eq!(with("today 9:", policy), Err(ErrorKind::InPast.at(0..8)));
+
+            let policy = MeridiemPolicy::Strict24h;
+            assert_eq!(with("3:30", policy), Ok(13.hours() + 30.minutes()));
+            assert_eq!(with("3:30pm", policy), Ok(1.hours() + 30.minutes()));
+            assert_eq!(with("17:30", policy), Ok(3.hours() + 30.minutes()));
+            assert_eq!(with("today 3:30", policy), Err(ErrorKind::InPast.at(0..10)));
+        }
+
+        #[test]
+        fn current_time() {
+            use crate::time::relative::duration_until_time;
+            use time::Time;
 
+            // the local zone and time are used by default
             assert_eq!(
-                interpret_single("3:12pm").unwrap().whole_seconds(),
-                duration_until_time(Time::from_hms(3 + 12, 12, 0).unwrap()).whole_seconds()
+                interpret_single("3pm").unwrap().whole_minutes(),
+                duration_until_time(Time::from_hms(15, 0, 0).unwrap()).whole_minutes()
             );
+        }
+    }
 
+    mod iso {
+        use super::*;
+        use crate::time::relative::{duration_of_months, now};
+
+        #[test]
+        fn durations() {
             assert_eq!(
-                interpret_single("5:12:30 am").unwrap().whole_seconds(),
-                duration_until_time(Time::from_hms(5, 12, 30).unwrap()).whole_seconds()
+                interpret_single("PT1H30M").unwrap(),
+                1.hours() + 30.minutes()
+            );
+            assert_eq!(interpret_single("P2DT3H").unwrap(), 2.days() + 3.hours());
+            assert_eq!(interpret_single("pt0,5s").unwrap(), 500.milliseconds());
+            assert_eq!(interpret_single(" P1W ").unwrap(), 7.days());
+            assert_eq!(
+                interpret_single("P1Y2M").unwrap().whole_seconds(),
+                duration_of_months(14).unwrap().whole_seconds()
+            );
+            assert_eq!(
+                interpret_single("P10000Y"),
+                Err(ErrorKind::TooLong.at(0..7))
             );
         }
+
+        #[test]
+        fn instants() {
+            use crate::time::iso::format_instant;
+
+            let target = now() + 2.hours();
+            let duration = interpret_single(&format_instant(target)).unwrap();
+            assert_eq!(duration.whole_minutes(), 119);
+
+            let utc = format_instant(target.to_offset(time::UtcOffset::UTC));
+            assert!(utc.ends_with('Z'));
+            assert_eq!(interpret_single(&utc).unwrap().whole_minutes(), 119);
+
+            assert_eq!(
+                interpret_single("2000-01-01T00:00:00Z"),
+                Err(ErrorKind::InPast.at(0..20))
+            );
+        }
+
+        #[test]
+        fn mixed_formats() {
+            assert_eq!(
+                interpret_single("PT1H 5m"),
+                Err(ErrorKind::ClashingFormats.at(5..6))
+            );
+            assert_eq!(
+                interpret_single("3h PT1H"),
+                Err(ErrorKind::ClashingFormats.at(0..1))
+            );
+            assert!(interpret_single("P1H").is_err());
+            assert!(interpret_single("PT").is_err());
+        }
     }
 
     mod errors {
@@ -186,7 +793,51 @@ mod tests {
                 "10s 300ms 10",
                 "13:0:0am",
                 "3pm 10",
+                "24:00",
+                "at",
+                "at 25",
+                "at 3m",
+                "17h30m at",
+                "tomorrow 3h",
+                "tomorrow fri 5pm",
+                "5pm tomorrow 3",
+                "half past",
+                "quarter to five past",
+                "of",
             ])
         }
+
+        #[test]
+        fn error_spans() {
+            let span = |input| interpret_single(input).unwrap_err().span;
+
+            assert_eq!(span("3h 4x"), 4..5);
+            assert_eq!(span("3h 4$"), 4..5);
+            assert_eq!(span("3:4:5:6"), 5..6);
+            assert_eq!(span("10s 300ms 10"), 10..12);
+            assert_eq!(span("3pm 10"), 4..6);
+            assert_eq!(span("2pm 3h"), 4..5);
+            assert_eq!(span("five minutes half past"), 18..22);
+            assert_eq!(span("an hour and a half of"), 19..21);
+        }
+
+        #[test]
+        fn suggestions() {
+            let suggestion = |input| interpret_single(input).unwrap_err().suggestion();
+
+            assert_eq!(
+                interpret_single("5 mni"),
+                Err(ErrorKind::MisspeltUnit {
+                    unit: "mni".to_string(),
+                    suggestion: "min"
+                }
+                .at(2..5))
+            );
+            assert_eq!(suggestion("2 hsr"), Some("hrs"));
+            assert_eq!(suggestion("3 pn"), Some("pm"));
+            assert_eq!(suggestion("frday 5pm"), Some("friday"));
+            assert_eq!(suggestion("5 xyz"), None);
+            assert_eq!(suggestion("5x"), None);
+        }
     }
 }
@@ -1,12 +1,15 @@
+use std::ops::Range;
+
 use az::SaturatingAs;
-use time::{ext::NumericalDuration, Duration};
+use time::{ext::NumericalDuration, Duration, Time};
 
 use crate::time::{
     meridiem::{self, Meridiem},
     relative,
+    units::TimeUnit,
 };
 
-use super::{parser::Token, Error, Result};
+use super::{parser::Token, ErrorKind, Options, Reference, Result};
 
 #[derive(Debug, PartialEq, Eq)]
 pub(super) enum InputFormat {
@@ -14,52 +17,200 @@ pub(super) enum InputFormat {
     /// Does not check that it is a number.
     SingleNumber,
     /// Checks that the Vec<Token> has a separator ":",
-    /// or has am/pm.
+    /// has am/pm, has a day, has a time zone or has "at".
     Time,
+    /// Checks that the Vec<Token> has an ISO 8601 duration or date and time.
+    Iso,
     /// If none of the other formats have been matched.
     Units,
 }
 
-/// Tries to evaluate a list of tokens to a duration.
+/// Tries to evaluate a list of tokens and their spans to a duration, with
+/// specific times relative to `reference` and read using `options`.
 ///
 /// # Errors
 /// Errors if the list does not match any known format.
-/// See `parse::parse_input` for more details on valid formats.
-pub(super) fn eval(tokens: &[Token]) -> Result<Duration> {
+/// See [`super::interpret_single`] for more details on valid formats.
+pub(super) fn eval(
+    tokens: &[(Token, Range<usize>)],
+    reference: Reference,
+    options: Options,
+) -> Result<Duration> {
     log::trace!("parsing tokens");
     if tokens.is_empty() {
         log::trace!("no tokens found");
-        return Err(Error::Empty);
+        return Err(ErrorKind::Empty.at(0..0));
     };
 
-    let format = get_tokens_format(tokens);
+    if let Some(i) = offset_position(tokens) {
+        return eval_offset(tokens, i, reference, options);
+    }
+
+    let (tokens, spans): (Vec<_>, Vec<_>) = tokens.iter().cloned().unzip();
+    let format = get_tokens_format(&tokens);
     log::trace!("tokens are in {format:?} format");
 
     match format {
-        InputFormat::SingleNumber => eval_single_number(tokens),
-        InputFormat::Time => eval_time(tokens),
-        InputFormat::Units => eval_units(tokens),
+        InputFormat::SingleNumber => eval_single_number(&tokens, &spans),
+        InputFormat::Time => eval_time(&tokens, &spans, reference, options),
+        InputFormat::Iso => eval_iso(&tokens, &spans, reference),
+        InputFormat::Units => eval_units(&tokens, &spans, reference),
+    }
+}
+
+/// The span covering all of `spans`.
+fn full_span(spans: &[Range<usize>]) -> Range<usize> {
+    match spans {
+        [] => 0..0,
+        [first, .., last] => first.start..last.end,
+        [only] => only.clone(),
     }
 }
 
 /// Tries to find the input format of the given list of tokens.
 fn get_tokens_format(tokens: &[Token]) -> InputFormat {
-    if tokens.len() == 1 {
+    let is_time_token = |t: &Token| {
+        matches!(
+            t,
+            Token::Separator | Token::At | Token::Meridiem(_) | Token::Date(_) | Token::Zone(_)
+        )
+    };
+
+    let is_iso_token = |t: &Token| matches!(t, Token::IsoDuration(_) | Token::IsoInstant(_));
+
+    // a day by itself is still a time (midnight)
+    if tokens.iter().any(is_iso_token) {
+        InputFormat::Iso
+    } else if tokens.len() == 1 && !matches!(tokens[0], Token::Date(_) | Token::Zone(_)) {
         InputFormat::SingleNumber
-    } else if tokens.contains(&Token::Separator)
-        || tokens.iter().any(|t| matches!(t, Token::Meridiem(_)))
-    {
+    } else if tokens.iter().any(is_time_token) || is_24h_with_h(tokens) {
         InputFormat::Time
     } else {
         InputFormat::Units
     }
 }
 
+/// Whether `tokens` are a specific time, like "5pm" or "10m before 3pm",
+/// rather than a length of time, so their duration depends on when they are
+/// evaluated.
+pub(super) fn is_time(tokens: &[(Token, Range<usize>)]) -> bool {
+    if offset_position(tokens).is_some()
+        || tokens
+            .iter()
+            .any(|(t, _)| matches!(t, Token::IsoInstant(_)))
+    {
+        return true;
+    }
+    let tokens: Vec<_> = tokens.iter().map(|(t, _)| *t).collect();
+    get_tokens_format(&tokens) == InputFormat::Time
+}
+
+/// Whether `tokens` are a 24 hour time with "h" as the separator, like
+/// "17h30".
+///
+/// Only hours of 13-23 are times without "at", as they cannot be am/pm.
+/// Smaller hours like "3h4" are still durations.
+fn is_24h_with_h(tokens: &[Token]) -> bool {
+    matches!(
+        tokens,
+        [Token::Number(h), Token::Unit(TimeUnit::Hour), Token::Number(m)]
+            if h.fract() == 0.0 && (13.0..24.0).contains(h) && m.fract() == 0.0 && *m < 60.0
+    )
+}
+
+/// Finds the token that splits an offset from the time it offsets.
+///
+/// The first "before" or "after" is used, so "5m before 10m before 3pm" is 5
+/// minutes before "10m before 3pm". Otherwise the last "+" or "-" is used, so
+/// "3pm - 10m - 5m" is 5 minutes before "3pm - 10m".
+fn offset_position(tokens: &[(Token, Range<usize>)]) -> Option<usize> {
+    tokens
+        .iter()
+        .position(|(t, _)| matches!(t, Token::Before | Token::After))
+        .or_else(|| {
+            tokens
+                .iter()
+                .rposition(|(t, _)| matches!(t, Token::Plus | Token::Minus))
+        })
+}
+
+/// Tries to parse a token list as a time offset by a duration, like "10m
+/// before 3pm", "20m after 9am", "3pm - 10m" or "9am + 20m".
+///
+/// "before" and "after" come after the duration, while "+" and "-" come after
+/// the time. The time is found as if now was the duration later ("before") or
+/// earlier ("after"), so this is the next time that is the duration away from
+/// the time. That means "20m after 9am" at 9:10am is in 10 minutes, not
+/// tomorrow.
+fn eval_offset(
+    tokens: &[(Token, Range<usize>)],
+    index: usize,
+    reference: Reference,
+    options: Options,
+) -> Result<Duration> {
+    let (op, op_span) = &tokens[index];
+    let (left, right) = (&tokens[..index], &tokens[index + 1..]);
+    let (offset, time, is_before) = match op {
+        Token::Before => (left, right, true),
+        Token::After => (left, right, false),
+        Token::Minus => (right, left, true),
+        Token::Plus => (right, left, false),
+        _ => unreachable!("should only be called with an offset token"),
+    };
+    if offset.is_empty() || time.is_empty() {
+        log::trace!("found an offset without both sides");
+        return Err(ErrorKind::InvalidOffset.at(op_span.clone()));
+    }
+
+    let side_span = |side: &[(Token, Range<usize>)]| {
+        let spans = side
+            .iter()
+            .map(|(_, span)| span.clone())
+            .collect::<Vec<_>>();
+        full_span(&spans)
+    };
+    let side_format = |side: &[(Token, Range<usize>)]| {
+        let tokens = side.iter().map(|(t, _)| *t).collect::<Vec<_>>();
+        get_tokens_format(&tokens)
+    };
+
+    let is_duration = !offset.iter().any(|(t, _)| t.is_offset())
+        && matches!(
+            side_format(offset),
+            InputFormat::SingleNumber | InputFormat::Units
+        );
+    if !is_duration {
+        log::trace!("offset is not a duration");
+        return Err(ErrorKind::InvalidOffset.at(side_span(offset)));
+    }
+    let is_time = time.iter().any(|(t, _)| t.is_offset())
+        || match side_format(time) {
+            InputFormat::Time => true,
+            InputFormat::Iso => matches!(time, [(Token::IsoInstant(_), _)]),
+            InputFormat::SingleNumber | InputFormat::Units => false,
+        };
+    if !is_time {
+        log::trace!("offset is not from a time");
+        return Err(ErrorKind::InvalidOffset.at(side_span(time)));
+    }
+
+    let offset_span = side_span(offset);
+    let offset = eval(offset, reference, options)?;
+    log::trace!("offsetting time by {offset}, before: {is_before}");
+    let now = if is_before {
+        reference.now.checked_add(offset)
+    } else {
+        reference.now.checked_sub(offset)
+    };
+    let now = now.ok_or_else(|| ErrorKind::TooLong.at(offset_span))?;
+    eval(time, Reference { now, ..reference }, options)
+}
+
 /// Tries to parse a token list as a single number.
-fn eval_single_number(tokens: &[Token]) -> Result<Duration> {
+fn eval_single_number(tokens: &[Token], spans: &[Range<usize>]) -> Result<Duration> {
     let Token::Number(n) = tokens[0] else {
         log::trace!("single token is not a number");
-        return Err(Error::Empty);
+        return Err(ErrorKind::Empty.at(spans[0].clone()));
     };
     log::trace!("successfully parsed as {n} minutes");
     Ok(n.minutes())
@@ -67,23 +218,67 @@ fn eval_single_number(tokens: &[Token]) -> Result<Duration> {
 
 /// Tries to parse a token list as a specific time,
 /// in 12h or 24h format.
-fn eval_time(tokens: &[Token]) -> Result<Duration> {
+///
+/// Hours of 0 or 13-23 are always 24h. Hours 1-12 with no am/pm are read
+/// using the meridiem policy of `options`, unless the time starts with "at"
+/// which forces 24h. "h" can also be used as the separator between hours and
+/// minutes after "at", or when the hour is 13-23 like "17h30".
+///
+/// A day can be given at the start or end, like "tomorrow 9am" or "5pm fri".
+/// Times on a specific day are midnight if only the day is given.
+///
+/// A time zone can be given at the very end, like "9:30am europe/berlin",
+/// which the time and day are then in. Otherwise they are in the zone of
+/// `reference`.
+fn eval_time(
+    tokens: &[Token],
+    spans: &[Range<usize>],
+    reference: Reference,
+    options: Options,
+) -> Result<Duration> {
+    let input_span = full_span(spans);
     let mut meridiem: Option<Meridiem> = None;
     let mut time_sections: [u8; 3] = [0, 0, 0];
     // 0 = hour, 1 = min, 2 = sec
     let mut current_unit = 0;
 
-    for token in tokens {
+    let (zone, tokens, spans) = match tokens {
+        [rest @ .., Token::Zone(z)] => (*z, rest, &spans[..rest.len()]),
+        tokens => (reference.zone, tokens, spans),
+    };
+    let now = reference.now;
+    let duration_until = |time| relative::next_occurrence_from(now, time, zone) - now;
+    if tokens.is_empty() {
+        log::trace!("found no time before the time zone");
+        return Err(ErrorKind::Empty.at(input_span.start..input_span.start));
+    }
+    let (target_date, tokens, spans) = match tokens {
+        [Token::Date(d), rest @ ..] => (Some(*d), rest, &spans[1..]),
+        [rest @ .., Token::Date(d)] => (Some(*d), rest, &spans[..rest.len()]),
+        tokens => (None, tokens, spans),
+    };
+    let (is_24h, tokens, spans) = match tokens {
+        [Token::At, rest @ ..] => (true, rest, &spans[1..]),
+        tokens => (false, tokens, spans),
+    };
+    if is_24h && tokens.is_empty() {
+        log::trace!("found no time after \"at\"");
+        return Err(ErrorKind::Empty.at(input_span.end..input_span.end));
+    }
+    let time_span = full_span(spans);
+    let h_separator = is_24h || is_24h_with_h(tokens);
+
+    for (token, span) in tokens.iter().zip(spans) {
         log::trace!("parsing token {token:?}");
+        let err = |kind: ErrorKind| Err(kind.at(span.clone()));
 
         // There should be a maximum of one am/pm
         // This would not run if it was set on the last iteration
         if meridiem.is_some() {
             log::trace!("found token after a meridiem");
-            return Err(Error::Unknown);
+            return err(ErrorKind::Unknown);
         };
 
-        #[expect(clippy::match_wildcard_for_single_variants)]
         match token {
             Token::Separator => {
                 current_unit += 1;
@@ -91,9 +286,14 @@ fn eval_time(tokens: &[Token]) -> Result<Duration> {
                 // check needs to be here to avoid an index error
                 if current_unit > 2 {
                     log::trace!("found more than 2 separators");
-                    return Err(Error::TooManySeparators);
+                    return err(ErrorKind::TooManySeparators);
                 }
             }
+            // "17h30" style, so that "3h4" is still a duration
+            Token::Unit(TimeUnit::Hour) if h_separator && current_unit == 0 => {
+                log::trace!("using hour unit as a separator");
+                current_unit += 1;
+            }
             // only allow times with integers
             Token::Number(n) if n.fract() == 0.0 => {
                 log::trace!("adding time {n} to section {current_unit}");
@@ -101,7 +301,7 @@ fn eval_time(tokens: &[Token]) -> Result<Duration> {
             }
             Token::Number(n) => {
                 log::trace!("time {n} is not an integer");
-                return Err(Error::InvalidNumber(n.to_string()));
+                return err(ErrorKind::InvalidNumber(n.to_string()));
             }
             Token::Meridiem(m) => {
                 log::trace!("setting meridiem to {m:?}");
@@ -109,27 +309,47 @@ fn eval_time(tokens: &[Token]) -> Result<Duration> {
             }
             _ => {
                 log::trace!("token is not any accepted token in the time format");
-                return Err(Error::ClashingFormats);
+                return err(ErrorKind::ClashingFormats);
             }
         }
     }
 
     let [h, m, s] = time_sections;
+    let invalid_time = || ErrorKind::Unknown.at(time_span.clone());
 
-    let duration = if let Some(meri) = meridiem {
-        log::trace!("setting to closest {h}:{m}:{s} {meri:?}");
-        relative::duration_until_time(meridiem::new_12h_time(h, m, s, meri).ok_or(Error::Unknown)?)
+    let times = if let Some(meri) = meridiem {
+        log::trace!("setting to {h}:{m}:{s} {meri:?}");
+        vec![meridiem::new_12h_time(h, m, s, meri).ok_or_else(invalid_time)?]
+    } else if is_24h || h == 0 || h > 12 {
+        log::trace!("setting to 24h time {h}:{m}:{s}");
+        vec![Time::from_hms(h, m, s).map_err(|_| invalid_time())?]
     } else {
-        log::trace!("setting to closest {h}:{m}:{s}");
-
-        // find the one that is closest to now
-        let am_time = meridiem::new_12h_time(h, m, s, Meridiem::Ante).ok_or(Error::Unknown)?;
-        let pm_time = meridiem::new_12h_time(h, m, s, Meridiem::Post).ok_or(Error::Unknown)?;
+        let policy = options.meridiem_policy;
+        log::trace!("setting to {h}:{m}:{s} using {policy:?}");
+        match policy.meridiems(h, target_date.is_some()) {
+            [] => vec![Time::from_hms(h, m, s).map_err(|_| invalid_time())?],
+            meridiems => meridiems
+                .iter()
+                .map(|meri| meridiem::new_12h_time(h, m, s, *meri).ok_or_else(invalid_time))
+                .collect::<Result<_>>()?,
+        }
+    };
 
-        Duration::min(
-            relative::duration_until_time(am_time),
-            relative::duration_until_time(pm_time),
-        )
+    // use whichever time comes first
+    let duration = if let Some(target_date) = target_date {
+        log::trace!("setting to {times:?} on {target_date:?}");
+        times
+            .into_iter()
+            .map(|time| relative::occurrence_on_from(now, target_date, time, zone) - now)
+            .filter(|duration| !duration.is_negative())
+            .min()
+            .ok_or_else(|| ErrorKind::InPast.at(input_span))?
+    } else {
+        times
+            .into_iter()
+            .map(duration_until)
+            .min()
+            .expect("should have at least one time")
     };
 
     log::trace!("successfully parsed time, returning {duration}");
@@ -137,40 +357,109 @@ fn eval_time(tokens: &[Token]) -> Result<Duration> {
     Ok(duration)
 }
 
+/// Tries to parse a token list as a single ISO 8601 duration or date and
+/// time.
+///
+/// Like [`eval_units`], whole months and years are added using the calendar.
+fn eval_iso(tokens: &[Token], spans: &[Range<usize>], reference: Reference) -> Result<Duration> {
+    match tokens {
+        [Token::IsoDuration(duration)] => {
+            let mut total = duration.duration;
+            if duration.months != 0 {
+                log::trace!("adding {} calendar months", duration.months);
+                total = relative::duration_of_months_from(
+                    reference.now,
+                    duration.months,
+                    reference.zone,
+                )
+                .and_then(|months| total.checked_add(months))
+                .ok_or_else(|| ErrorKind::TooLong.at(spans[0].clone()))?;
+            }
+            log::trace!("successfully parsed iso duration as {total}");
+            Ok(total)
+        }
+        [Token::IsoInstant(instant)] => {
+            let duration = instant.resolve(reference.zone) - reference.now;
+            if duration.is_negative() {
+                return Err(ErrorKind::InPast.at(spans[0].clone()));
+            }
+            log::trace!("successfully parsed iso instant, returning {duration}");
+            Ok(duration)
+        }
+        _ => {
+            log::trace!("found an iso 8601 value with other tokens");
+            // point at whatever isn't the iso value
+            let i = tokens
+                .iter()
+                .position(|t| !matches!(t, Token::IsoDuration(_) | Token::IsoInstant(_)))
+                .unwrap_or(1);
+            Err(ErrorKind::ClashingFormats.at(spans[i].clone()))
+        }
+    }
+}
+
 /// Tries to parse a token list as a duration with units.
-fn eval_units(tokens: &[Token]) -> Result<Duration> {
+///
+/// Whole months and years are added using the calendar, so "1mo" is until
+/// the same date next month.
+fn eval_units(tokens: &[Token], spans: &[Range<usize>], reference: Reference) -> Result<Duration> {
     let mut total_duration = Duration::ZERO;
+    let mut calendar_months: i64 = 0;
+    // the span of the last calendar unit, for when there are too many months
+    let mut months_span = 0..0;
     let mut current_number = 0.0;
 
-    for token in tokens {
+    for (token, span) in tokens.iter().zip(spans) {
         log::trace!("parsing token {token:?}");
 
         match token {
             Token::Number(n) => current_number = *n,
-            Token::Unit(u) => total_duration += u.to_duration(current_number),
-            _ => return Err(Error::ClashingFormats),
+            Token::Unit(u) => {
+                if let Some(months) = u.calendar_months() {
+                    let whole = current_number.trunc();
+                    calendar_months = whole
+                        .saturating_as::<i64>()
+                        .checked_mul(months)
+                        .and_then(|months| calendar_months.checked_add(months))
+                        .ok_or_else(|| ErrorKind::TooLong.at(span.clone()))?;
+                    months_span = span.clone();
+                    total_duration += u.to_duration(current_number - whole);
+                } else {
+                    total_duration += u.to_duration(current_number);
+                }
+            }
+            _ => return Err(ErrorKind::ClashingFormats.at(span.clone())),
         }
     }
 
+    if calendar_months != 0 {
+        log::trace!("adding {calendar_months} calendar months");
+        total_duration =
+            relative::duration_of_months_from(reference.now, calendar_months, reference.zone)
+                .and_then(|months| total_duration.checked_add(months))
+                .ok_or_else(|| ErrorKind::TooLong.at(months_span))?;
+    }
+
     log::trace!("parsed units so far to {total_duration}");
 
     // the above only adds a number to the total when a unit is encountered
     // add the trailing number if it exists, as there is no unit after
     if let Token::Number(n) = tokens.last().expect("should have at least one token") {
+        let span = spans[spans.len() - 1].clone();
         log::trace!("found ending number {n}");
         let Token::Unit(unit) = tokens[tokens.len() - 2] else {
             log::warn!(
                 r#"token before a the last number should always be a unit!\
                 found non-unit before {n} in tokens {tokens:?}"#
             );
-            return Err(Error::Unknown);
+            return Err(ErrorKind::Unknown.at(span));
         };
 
         log::trace!("adding {n} in unit smaller than {unit:?}");
 
         total_duration += unit
             .smaller_unit()
-            .ok_or(Error::SmallerThanMilli(*n))?
+            .ok_or_else(|| ErrorKind::SmallerThanMilli(*n).at(span))?
             .to_duration(*n);
     };
 
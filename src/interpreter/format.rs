@@ -0,0 +1,135 @@
+use std::fmt::Write;
+
+use time::Duration;
+
+use crate::time::units::{self, TimeUnit};
+
+/// Formats a duration as an input for [`super::interpret_single`], like
+/// "1h 30m 5s".
+///
+/// The units used are weeks, days, hours, minutes, seconds and milliseconds,
+/// and units that are 0 are left out. Anything smaller than a millisecond is
+/// removed, as it cannot be entered anyways.
+///
+/// Interpreting the output gives back the same duration, as long as it is not
+/// negative and is a whole number of milliseconds. Negative durations are
+/// formatted as "0".
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::{format_duration, interpret_single};
+/// use time::{ext::NumericalDuration, Duration};
+///
+/// let duration = 1.hours() + 30.minutes() + 5.seconds();
+/// assert_eq!(format_duration(duration), "1h 30m 5s");
+/// assert_eq!(interpret_single("1h 30m 5s").unwrap(), duration);
+///
+/// assert_eq!(format_duration(9.days()), "1w 2d");
+/// assert_eq!(format_duration(Duration::ZERO), "0");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    if !duration.is_positive() {
+        return "0".to_string();
+    }
+
+    // duration is positive so the casts are fine
+    let sections = [
+        (duration.whole_weeks() as u64, TimeUnit::Week),
+        (
+            duration.whole_days() as u64 % units::DAYS_IN_WEEK,
+            TimeUnit::Day,
+        ),
+        (
+            duration.whole_hours() as u64 % units::HOURS_IN_DAY,
+            TimeUnit::Hour,
+        ),
+        (
+            duration.whole_minutes() as u64 % units::MINS_IN_HOUR,
+            TimeUnit::Min,
+        ),
+        (
+            duration.whole_seconds() as u64 % units::SECS_IN_MIN,
+            TimeUnit::Sec,
+        ),
+        (duration.subsec_milliseconds() as u64, TimeUnit::Milli),
+    ];
+
+    let mut output = String::new();
+    for (value, unit) in sections.into_iter().filter(|(value, _)| *value != 0) {
+        if !output.is_empty() {
+            output.push(' ');
+        }
+        write!(output, "{value}{}", unit_token(unit)).expect("writing to a string cannot fail");
+    }
+
+    if output.is_empty() {
+        // less than a millisecond
+        output.push('0');
+    }
+    output
+}
+
+/// The shortest token for a unit, which is what is used when formatting.
+const fn unit_token(unit: TimeUnit) -> &'static str {
+    match unit {
+        TimeUnit::Milli => TimeUnit::MILLI_TOKENS[0],
+        TimeUnit::Sec => TimeUnit::SEC_TOKENS[0],
+        TimeUnit::Min => TimeUnit::MIN_TOKENS[0],
+        TimeUnit::Hour => TimeUnit::HOUR_TOKENS[0],
+        TimeUnit::Day => TimeUnit::DAY_TOKENS[0],
+        TimeUnit::Week => TimeUnit::WEEK_TOKENS[0],
+        TimeUnit::Month => TimeUnit::MONTH_TOKENS[0],
+        TimeUnit::Year => TimeUnit::YEAR_TOKENS[0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use time::ext::NumericalDuration;
+
+    use super::*;
+    use crate::interpreter::interpret_single;
+
+    /// Durations made of every combination of a few amounts of each unit.
+    fn durations() -> impl Iterator<Item = Duration> {
+        const AMOUNTS: [i32; 4] = [0, 1, 2, 59];
+        let units = [
+            Duration::WEEK,
+            Duration::DAY,
+            Duration::HOUR,
+            Duration::MINUTE,
+            Duration::SECOND,
+            Duration::MILLISECOND,
+        ];
+        units
+            .into_iter()
+            .map(|unit| AMOUNTS.map(|amount| unit * amount))
+            .multi_cartesian_product()
+            .map(|parts| parts.into_iter().sum())
+    }
+
+    #[test]
+    fn formats_units() {
+        assert_eq!(format_duration(3.hours() + 21.minutes()), "3h 21m");
+        assert_eq!(
+            format_duration(8.days() + 23.hours() + 5.seconds() + 91.milliseconds()),
+            "1w 1d 23h 5s 91ms"
+        );
+        assert_eq!(format_duration(49.milliseconds()), "49ms");
+        assert_eq!(format_duration(10.microseconds()), "0");
+        assert_eq!(format_duration((-5).minutes()), "0");
+    }
+
+    #[test]
+    fn round_trip() {
+        for duration in durations() {
+            let formatted = format_duration(duration);
+            assert_eq!(
+                interpret_single(&formatted),
+                Ok(duration),
+                "{duration} was formatted as {formatted}"
+            );
+        }
+    }
+}
@@ -1,80 +1,245 @@
-use super::{Error, Result};
+use std::ops::Range;
+
+use crate::time::{date, iso, locale::Vocabulary, zone};
+
+use super::{ErrorKind, Result};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum GroupKind {
     Number,
     Text,
     Separator,
+    Date,
+    Iso,
+    Zone,
+    Sign,
 }
 
 impl TryFrom<char> for GroupKind {
-    type Error = Error;
+    type Error = ErrorKind;
 
-    fn try_from(value: char) -> Result<Self> {
-        if value.is_ascii_alphabetic() {
+    fn try_from(value: char) -> Result<Self, ErrorKind> {
+        if value.is_alphabetic() {
             Ok(Self::Text)
         } else if value.is_ascii_digit() || value == '.' {
             Ok(Self::Number)
         } else if value == ':' {
             Ok(Self::Separator)
+        } else if value == '+' || value == '-' {
+            Ok(Self::Sign)
         } else {
-            Err(Error::InvalidCharacter(value))
+            Err(ErrorKind::InvalidCharacter(value))
         }
     }
 }
 
 /// A string that has one 'type' of characters.
 ///
-/// The three variants are:
-/// - `Text` if all characters are letters.
-/// - `Number` if all characters are digits or ".".
+/// The seven variants are:
+/// - `Text` if all characters are letters, in lowercase.
+/// - `Number` if all characters are digits or ".". Fractions like "1/2" are
+///   stored as decimals, and decimal commas as ".".
 /// - `Separator` if the string is ":".
-#[derive(Debug, PartialEq, Eq)]
+/// - `Date` if the string is an ISO 8601 date like "2026-12-24".
+/// - `Iso` if the string is an ISO 8601 duration like "pt1h30m" or date and
+///   time like "2026-10-16t17:00:00+02:00".
+/// - `Zone` if the string is a time zone like "europe/berlin" or "utc+2".
+/// - `Sign` if the string is "+" or "-", outside of a date, ISO 8601 value or
+///   time zone.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub(super) struct Group {
     pub variant: GroupKind,
     pub string: String,
+    /// The byte range of the input this group came from.
+    pub span: Range<usize>,
 }
 
 /// Splits a string into separate strings with types.
 ///
 /// See [`Group`] for the variants and conditions.
 ///
+/// Numbers can use the decimal separator of `vocabulary` as well as ".", like
+/// "1,5". A "." straight after a word ends an abbreviation, like "min.", and
+/// is left out.
+///
 /// # Errors
 /// Errors if any character could not be parsed into a token.
-/// Characters `[A-Za-z0-9.:]` are the only accepted characters.
-pub(super) fn lex(input: &str) -> Result<Vec<Group>> {
-    // only ascii is parsed anyways
-    let input = input.to_ascii_lowercase().replace(' ', "");
+/// Letters, digits, ".", ":", "+" and "-" are the only accepted characters,
+/// other than "/" inside a fraction, "," inside an ISO 8601 value, "/" and "_"
+/// inside a time zone and the decimal separator between digits. Also errors
+/// if a fraction divides by 0.
+///
+/// Spaces are ignored, other than separating words and dates from what comes
+/// after them. A "-" between two words separates them the same way, like
+/// "twenty-five". A whole number followed by a space and a fraction is added to
+/// it, so "1 1/2" is 1.5.
+pub(super) fn lex(input: &str, vocabulary: &Vocabulary) -> Result<Vec<Group>> {
+    // keeps the byte length the same, other letters are lowercased as they
+    // are added to a group
+    let input = input.to_ascii_lowercase();
 
     let mut token_list: Vec<Group> = Vec::new();
     let mut prev_token_type = GroupKind::Separator; // will be overwritten
 
-    for ch in input.chars() {
+    let mut chars = input.char_indices();
+    while let Some((i, ch)) = chars.next() {
         log::trace!("parsing character {ch:?}");
-        let curr_token_type = GroupKind::try_from(ch)?;
+        // spaces are ignored, but need to be kept until here so that a date
+        // doesn't join with a time after it, and words stay separate.
+        if ch == ' ' {
+            if prev_token_type == GroupKind::Text {
+                // forces the next group to be new
+                prev_token_type = GroupKind::Separator;
+            }
+            continue;
+        }
+
+        // a "-" between words, like "twenty-five", separates them like a space
+        if ch == '-'
+            && input[..i].ends_with(char::is_alphabetic)
+            && input[i + 1..].starts_with(char::is_alphabetic)
+        {
+            prev_token_type = GroupKind::Separator;
+            continue;
+        }
+
+        if !input[..i].ends_with(|c: char| c.is_ascii_alphanumeric())
+            && let Some(len) =
+                iso::instant_len(&input[i..]).or_else(|| iso::duration_len(&input[i..]))
+        {
+            log::trace!("found an iso 8601 value");
+            token_list.push(Group {
+                variant: GroupKind::Iso,
+                string: input[i..i + len].to_string(),
+                span: i..i + len,
+            });
+            // forces the next group to be new
+            prev_token_type = GroupKind::Separator;
+            // iso values are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+            continue;
+        }
+
+        if ch.is_ascii_alphabetic() && !input[..i].ends_with(|c: char| c.is_ascii_alphanumeric()) {
+            if let Some(len) = zone::zone_len(&input[i..]) {
+                log::trace!("found a time zone");
+                token_list.push(Group {
+                    variant: GroupKind::Zone,
+                    string: input[i..i + len].to_string(),
+                    span: i..i + len,
+                });
+                // forces the next group to be new
+                prev_token_type = GroupKind::Separator;
+                // zones are all ascii, skip the rest of it
+                chars.by_ref().take(len - 1).for_each(drop);
+                continue;
+            }
+
+            // only zone names have a "/" in a word
+            let word = &input[i..input[i..].find(' ').map_or(input.len(), |end| i + end)];
+            if word.contains('/') {
+                return Err(ErrorKind::InvalidZone(word.to_string()).at(i..i + word.len()));
+            }
+        }
+
+        if prev_token_type != GroupKind::Number
+            && let Some(len) = date::iso_date_len(&input[i..])
+        {
+            log::trace!("found a date");
+            token_list.push(Group {
+                variant: GroupKind::Date,
+                string: input[i..i + len].to_string(),
+                span: i..i + len,
+            });
+            prev_token_type = GroupKind::Date;
+            // dates are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+            continue;
+        }
+
+        if ch.is_ascii_digit()
+            && !input[..i].ends_with(|c: char| c.is_ascii_digit() || c == '.')
+            && let Some(len) = fraction_len(&input[i..])
+        {
+            log::trace!("found a fraction");
+            let span = i..i + len;
+            let fraction = &input[span.clone()];
+            let value = parse_fraction(fraction)
+                .ok_or_else(|| ErrorKind::InvalidNumber(fraction.to_string()).at(span.clone()))?;
+
+            match token_list.last_mut() {
+                // "1 1/2", the space is the only thing that could be between
+                Some(last)
+                    if last.variant == GroupKind::Number
+                        && last.span.end < i
+                        && last.string.bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    let whole = last.string.parse::<f64>().expect("string is all digits");
+                    last.string = (whole + value).to_string();
+                    last.span.end = span.end;
+                }
+                _ => token_list.push(Group {
+                    variant: GroupKind::Number,
+                    string: value.to_string(),
+                    span,
+                }),
+            }
+            // forces the next group to be new, so a number after it doesn't join
+            prev_token_type = GroupKind::Separator;
+            // fractions are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+            continue;
+        }
 
-        // Always new token if its a separator
+        let next_is_digit = input[i + ch.len_utf8()..].starts_with(|c: char| c.is_ascii_digit());
+        if let Some(last) = token_list.last_mut()
+            && last.span.end == i
+        {
+            // "min." or "a.m.", the "." isn't part of the word
+            if ch == '.' && last.variant == GroupKind::Text && !next_is_digit {
+                log::trace!("ignoring the end of an abbreviation");
+                last.span.end = i + 1;
+                continue;
+            }
+            // "1,5", stored as "1.5" so it parses
+            if ch == vocabulary.decimal_separator
+                && last.variant == GroupKind::Number
+                && prev_token_type == GroupKind::Number
+                && next_is_digit
+            {
+                log::trace!("using {ch:?} as a decimal point");
+                last.string.push('.');
+                last.span.end = i + ch.len_utf8();
+                continue;
+            }
+        }
+
+        let char_span = i..i + ch.len_utf8();
+        let curr_token_type = GroupKind::try_from(ch).map_err(|e| e.at(char_span.clone()))?;
+
+        // Always new token if its a separator or sign
         let is_new_token = curr_token_type != prev_token_type
             || token_list.is_empty()
-            || curr_token_type == GroupKind::Separator;
+            || matches!(curr_token_type, GroupKind::Separator | GroupKind::Sign);
 
         if is_new_token {
             log::trace!("character is a new token");
             // create new token: add to the vec
             token_list.push(Group {
                 variant: curr_token_type,
-                string: ch.to_string(),
+                string: ch.to_lowercase().collect(),
+                span: char_span,
             });
 
             prev_token_type = curr_token_type;
         } else {
             log::trace!("character is same type as previous");
             // add to the last token in the vec
-            token_list
+            let last = token_list
                 .last_mut()
-                .expect("new token should always be appended first")
-                .string
-                .push(ch);
+                .expect("new token should always be appended first");
+            last.string.extend(ch.to_lowercase());
+            last.span.end = char_span.end;
         }
     }
 
@@ -82,9 +247,65 @@ pub(super) fn lex(input: &str) -> Result<Vec<Group>> {
     Ok(token_list)
 }
 
+/// Finds the length of the fraction at the start of `s`, like "1/2", if there
+/// is one.
+fn fraction_len(s: &str) -> Option<usize> {
+    let digits = |s: &str| s.bytes().take_while(u8::is_ascii_digit).count();
+
+    let numerator = digits(s);
+    if numerator == 0 || s.as_bytes().get(numerator) != Some(&b'/') {
+        return None;
+    }
+    let denominator = digits(&s[numerator + 1..]);
+    let len = numerator + 1 + denominator;
+    // "1/2.5" and "1/2/3" aren't fractions
+    let is_continued = s[len..].starts_with(['.', '/']);
+    (denominator != 0 && !is_continued).then_some(len)
+}
+
+/// Parses a fraction like "3/4", returning `None` if it is invalid or divides
+/// by 0.
+fn parse_fraction(s: &str) -> Option<f64> {
+    let (numerator, denominator) = s.split_once('/')?;
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_digits(numerator) || !is_digits(denominator) {
+        return None;
+    }
+
+    let numerator = numerator.parse::<f64>().ok()?;
+    let denominator = denominator.parse::<f64>().ok()?;
+    (denominator > 0.0).then(|| numerator / denominator)
+}
+
+/// Parses a number by itself, written as a decimal like "1.5", a fraction
+/// like "1/2" or a whole number and a fraction like "1 1/2".
+///
+/// Decimals can also use the decimal separator of `vocabulary`, like "1,5".
+///
+/// Returns `None` if `s` is anything else.
+pub(super) fn parse_number(s: &str, vocabulary: &Vocabulary) -> Option<f64> {
+    let s = s.replace(vocabulary.decimal_separator, ".");
+    let parts: Vec<&str> = s.split_whitespace().collect();
+    match parts.as_slice() {
+        [number] if number.bytes().all(|b| b.is_ascii_digit() || b == b'.') => {
+            number.parse::<f64>().ok()
+        }
+        [fraction] => parse_fraction(fraction),
+        [whole, fraction] if whole.bytes().all(|b| b.is_ascii_digit()) => {
+            Some(whole.parse::<f64>().ok()? + parse_fraction(fraction)?)
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::time::locale::{ENGLISH, GERMAN};
+
+    fn lex(input: &str) -> Result<Vec<Group>> {
+        super::lex(input, &ENGLISH)
+    }
 
     #[test]
     fn separate_time_unit() {
@@ -93,11 +314,13 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "1".to_string()
+                    string: "1".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "d".to_string()
+                    string: "d".to_string(),
+                    span: 1..2
                 },
             ])
         );
@@ -106,11 +329,13 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "1.3".to_string()
+                    string: "1.3".to_string(),
+                    span: 0..3
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "h".to_string()
+                    string: "h".to_string(),
+                    span: 4..5
                 },
             ])
         );
@@ -119,11 +344,13 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "3".to_string()
+                    string: "3".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "m".to_string()
+                    string: "m".to_string(),
+                    span: 1..2
                 },
             ])
         );
@@ -132,11 +359,13 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "94".to_string()
+                    string: "94".to_string(),
+                    span: 0..2
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "ms".to_string()
+                    string: "ms".to_string(),
+                    span: 3..5
                 },
             ])
         );
@@ -149,19 +378,23 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "1".to_string()
+                    string: "1".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "d".to_string()
+                    string: "d".to_string(),
+                    span: 1..2
                 },
                 Group {
                     variant: GroupKind::Number,
-                    string: "3".to_string()
+                    string: "3".to_string(),
+                    span: 2..3
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "h".to_string()
+                    string: "h".to_string(),
+                    span: 3..4
                 },
             ])
         );
@@ -170,27 +403,33 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "5".to_string()
+                    string: "5".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "h".to_string()
+                    string: "h".to_string(),
+                    span: 1..2
                 },
                 Group {
                     variant: GroupKind::Number,
-                    string: "92".to_string()
+                    string: "92".to_string(),
+                    span: 3..5
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "m".to_string()
+                    string: "m".to_string(),
+                    span: 5..6
                 },
                 Group {
                     variant: GroupKind::Number,
-                    string: "1".to_string()
+                    string: "1".to_string(),
+                    span: 7..8
                 },
                 Group {
                     variant: GroupKind::Text,
-                    string: "ms".to_string()
+                    string: "ms".to_string(),
+                    span: 8..10
                 },
             ])
         );
@@ -203,23 +442,28 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "3".to_string()
+                    string: "3".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Separator,
-                    string: ":".to_string()
+                    string: ":".to_string(),
+                    span: 1..2
                 },
                 Group {
                     variant: GroupKind::Number,
-                    string: "4".to_string()
+                    string: "4".to_string(),
+                    span: 2..3
                 },
                 Group {
                     variant: GroupKind::Separator,
-                    string: ":".to_string()
+                    string: ":".to_string(),
+                    span: 3..4
                 },
                 Group {
                     variant: GroupKind::Number,
-                    string: "7".to_string()
+                    string: "7".to_string(),
+                    span: 4..5
                 },
             ])
         );
@@ -228,21 +472,236 @@ mod tests {
             Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "1".to_string()
+                    string: "1".to_string(),
+                    span: 0..1
                 },
                 Group {
                     variant: GroupKind::Separator,
-                    string: ":".to_string()
+                    string: ":".to_string(),
+                    span: 1..2
                 },
                 Group {
                     variant: GroupKind::Separator,
-                    string: ":".to_string()
+                    string: ":".to_string(),
+                    span: 2..3
+                },
+                Group {
+                    variant: GroupKind::Number,
+                    string: "2".to_string(),
+                    span: 3..4
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn separate_date() {
+        assert_eq!(
+            lex("2026-12-24 18:00"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Date,
+                    string: "2026-12-24".to_string(),
+                    span: 0..10
+                },
+                Group {
+                    variant: GroupKind::Number,
+                    string: "18".to_string(),
+                    span: 11..13
+                },
+                Group {
+                    variant: GroupKind::Separator,
+                    string: ":".to_string(),
+                    span: 13..14
+                },
+                Group {
+                    variant: GroupKind::Number,
+                    string: "00".to_string(),
+                    span: 14..16
+                },
+            ])
+        );
+        // not a date, so the "-" is a sign
+        assert_eq!(
+            lex("3-4"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "3".to_string(),
+                    span: 0..1
+                },
+                Group {
+                    variant: GroupKind::Sign,
+                    string: "-".to_string(),
+                    span: 1..2
+                },
+                Group {
+                    variant: GroupKind::Number,
+                    string: "4".to_string(),
+                    span: 2..3
+                },
+            ])
+        );
+        assert_eq!(lex("3_4"), Err(ErrorKind::InvalidCharacter('_').at(1..2)));
+    }
+
+    #[test]
+    fn separate_zone() {
+        assert_eq!(
+            lex("9am europe/berlin"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "9".to_string(),
+                    span: 0..1
                 },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "am".to_string(),
+                    span: 1..3
+                },
+                Group {
+                    variant: GroupKind::Zone,
+                    string: "europe/berlin".to_string(),
+                    span: 4..17
+                },
+            ])
+        );
+        assert_eq!(
+            lex("5pm utc+2"),
+            Ok(vec![
                 Group {
                     variant: GroupKind::Number,
-                    string: "2".to_string()
+                    string: "5".to_string(),
+                    span: 0..1
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "pm".to_string(),
+                    span: 1..3
+                },
+                Group {
+                    variant: GroupKind::Zone,
+                    string: "utc+2".to_string(),
+                    span: 4..9
+                },
+            ])
+        );
+        assert_eq!(
+            lex("5pm mars/base"),
+            Err(ErrorKind::InvalidZone("mars/base".to_string()).at(4..13))
+        );
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(
+            lex("1/2h"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "0.5".to_string(),
+                    span: 0..3
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "h".to_string(),
+                    span: 3..4
+                },
+            ])
+        );
+        assert_eq!(
+            lex("1 1/2 hours"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "1.5".to_string(),
+                    span: 0..5
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "hours".to_string(),
+                    span: 6..11
+                },
+            ])
+        );
+        assert_eq!(
+            lex("3/4 2"),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "0.75".to_string(),
+                    span: 0..3
+                },
+                Group {
+                    variant: GroupKind::Number,
+                    string: "2".to_string(),
+                    span: 4..5
+                },
+            ])
+        );
+        assert_eq!(
+            lex("1/0h"),
+            Err(ErrorKind::InvalidNumber("1/0".to_string()).at(0..3))
+        );
+        assert_eq!(lex("1/2/3"), Err(ErrorKind::InvalidCharacter('/').at(1..2)));
+
+        assert_eq!(parse_number("1.5", &ENGLISH), Some(1.5));
+        assert_eq!(parse_number("1/4", &ENGLISH), Some(0.25));
+        assert_eq!(parse_number("2 1/2", &ENGLISH), Some(2.5));
+        assert_eq!(parse_number("2 h", &ENGLISH), None);
+        assert_eq!(parse_number("nan", &ENGLISH), None);
+        assert_eq!(parse_number("1,5", &ENGLISH), None);
+        assert_eq!(parse_number("1,5", &GERMAN), Some(1.5));
+    }
+
+    #[test]
+    fn localised() {
+        assert_eq!(
+            super::lex("1,5 Std", &GERMAN),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "1.5".to_string(),
+                    span: 0..3
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "std".to_string(),
+                    span: 4..7
+                },
+            ])
+        );
+        assert_eq!(
+            super::lex("Fünf Min.", &GERMAN),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Text,
+                    string: "fünf".to_string(),
+                    span: 0..5
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "min".to_string(),
+                    span: 6..10
+                },
+            ])
+        );
+        assert_eq!(
+            lex("5 p.m."),
+            Ok(vec![
+                Group {
+                    variant: GroupKind::Number,
+                    string: "5".to_string(),
+                    span: 0..1
+                },
+                Group {
+                    variant: GroupKind::Text,
+                    string: "pm".to_string(),
+                    span: 2..6
                 },
             ])
         );
+        assert_eq!(lex("1,5"), Err(ErrorKind::InvalidCharacter(',').at(1..2)));
     }
 }
@@ -1,78 +1,636 @@
-use az::SaturatingAs;
-use dyn_clone::DynClone;
 use itertools::{Either, Itertools};
 use std::{
     fmt::{self, Write},
-    iter::{self, Peekable},
+    iter,
+    ops::Range,
     sync::Arc,
 };
+use time::{Duration, OffsetDateTime};
 
-use super::{Error, Result};
+use crate::time::{
+    date, iso,
+    locale::Vocabulary,
+    relative,
+    zone::{self, Zone},
+};
+
+use super::{
+    interpret_single_at, interpret_single_with, is_time, lexer,
+    plan::{Node, Plan, FOREVER},
+    presets::{self, Presets},
+    random::{self, Random},
+    suggest, Error, ErrorKind, Options, Reference, Result,
+};
 
 // pratt parser based on
 // https://matklad.github.io/2020/04/13/simple-but-powerful-pratt-parsing.html
 
-pub fn interpret_multi(input: &str) -> Result<InputIter> {
-    let expr = parse(input)?;
-    Ok(InputIter::from(eval(expr)?))
+/// Interprets `input` as a sequence of timers, like "(25m + 5m) * 4".
+///
+/// Each timer is a valid input for [`super::interpret_single`], except that
+/// specific times can only be offset with "before" and "after", like "10m
+/// before 3pm". A "+" or "-" always joins timers instead, so "3pm + 20m" is a
+/// timer until 3pm and then a 20 minute timer, and "3pm - 10m" is a timer
+/// until 3pm that ends 10 minutes early.
+///
+/// # Errors
+/// Errors if `input` is not a valid sequence of timers.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::interpret_multi;
+///
+/// assert_eq!(interpret_multi("10m before 3pm + 5m").unwrap().len(), Some(2));
+/// assert_eq!(interpret_multi("3pm + 20m").unwrap().len(), Some(2));
+/// ```
+pub fn interpret_multi(input: &str) -> Result<Plan> {
+    interpret_multi_with(input, Options::default())
+}
+
+/// Interprets `input` like [`interpret_multi`], with every segment read using
+/// `options` instead of the defaults.
+///
+/// # Errors
+/// Errors if `input` is not a valid input for [`interpret_multi`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::{
+///     interpreter::{interpret_multi_with, Options},
+///     time::meridiem::MeridiemPolicy,
+/// };
+///
+/// let options = Options {
+///     meridiem_policy: MeridiemPolicy::Strict24h,
+///     ..Options::default()
+/// };
+/// let mut plan = interpret_multi_with("5m + 9:30", options).unwrap();
+/// assert_eq!(plan.len(), Some(2));
+/// ```
+pub fn interpret_multi_with(input: &str, options: Options) -> Result<Plan> {
+    interpret_multi_with_presets(input, options, &Presets::default())
+}
+
+/// Interprets `input` like [`interpret_multi_with`], where the names of
+/// `presets` can be used like durations, like "pomo * 2 + 1h".
+///
+/// # Errors
+/// Errors if `input` is not a valid input for [`interpret_multi`], uses a
+/// preset that is not in `presets`, or uses a preset that uses itself.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::{interpret_multi_with_presets, ErrorKind, Options, Presets};
+///
+/// let presets: Presets = "pomo = (25m + 5m) * 4".parse().unwrap();
+/// let options = Options::default();
+/// let plan = interpret_multi_with_presets("pomo + 1h", options, &presets).unwrap();
+/// assert_eq!(plan.len(), Some(9));
+///
+/// let error = interpret_multi_with_presets("pomdoro + 1h", options, &presets).unwrap_err();
+/// assert_eq!(error.kind, ErrorKind::UnknownPreset("pomdoro".to_string()));
+/// ```
+pub fn interpret_multi_with_presets(
+    input: &str,
+    options: Options,
+    presets: &Presets,
+) -> Result<Plan> {
+    interpret_multi_at(input, Reference::current(), options, presets)
+}
+
+/// Interprets `input` like [`interpret_multi_with_presets`], but as if it was
+/// interpreted at `reference` instead of now.
+///
+/// How many times "until" repeats depends on when the input is interpreted,
+/// so this gives the same plan again later, like when a timer is restored.
+/// The durations of specific times are still found when each segment starts.
+///
+/// # Errors
+/// Errors if `input` is not a valid input for [`interpret_multi`], see
+/// [`interpret_multi_with_presets`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::{
+///     interpreter::{interpret_multi_at, Options, Presets, Reference},
+///     time::zone::Zone,
+/// };
+/// use time::macros::{datetime, offset};
+///
+/// let reference = Reference {
+///     now: datetime!(2026-10-16 9:00 UTC),
+///     zone: Zone::Fixed(offset!(UTC)),
+/// };
+/// let (options, presets) = (Options::default(), Presets::default());
+/// let plan = interpret_multi_at("(25m + 5m) until 5pm", reference, options, &presets);
+/// assert_eq!(plan.unwrap().len(), Some(32));
+///
+/// let later = Reference {
+///     now: datetime!(2026-10-16 14:00 UTC),
+///     ..reference
+/// };
+/// let plan = interpret_multi_at("(25m + 5m) until 5pm", later, options, &presets);
+/// assert_eq!(plan.unwrap().len(), Some(12));
+/// ```
+pub fn interpret_multi_at(
+    input: &str,
+    reference: Reference,
+    options: Options,
+    presets: &Presets,
+) -> Result<Plan> {
+    let expr = parse_with(input, options, presets)?;
+    Ok(Plan::new(
+        eval(expr, Some(Duration::ZERO), reference)?.into_node(),
+    ))
+}
+
+/// Finds every error in `input`, instead of only the first like
+/// [`interpret_multi_with_presets`], so that they can all be fixed at once.
+///
+/// Each error has the span of the segment or operator that caused it, and
+/// they are sorted by where they are in `input`. Errors from joining segments
+/// together, like [`ErrorKind::MulDurations`], are only found once every
+/// segment is valid.
+///
+/// Returns no errors if `input` is valid.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::{diagnose_multi, ErrorKind, Options, Presets};
+///
+/// let (options, presets) = (Options::default(), Presets::default());
+/// let errors = diagnose_multi("5q + (25m + 5m) * 4 + 1h)", options, &presets);
+/// let kinds = errors.into_iter().map(|e| e.kind).collect::<Vec<_>>();
+/// assert_eq!(
+///     kinds,
+///     [ErrorKind::InvalidUnit("q".to_string()), ErrorKind::UnbalancedParens]
+/// );
+/// assert!(diagnose_multi("25m + 5m", options, &presets).is_empty());
+/// ```
+pub fn diagnose_multi(input: &str, options: Options, presets: &Presets) -> Vec<Error> {
+    match parse_all(input, options, presets, &mut Vec::new()) {
+        Ok(expr) => eval(expr, Some(Duration::ZERO), Reference::current())
+            .err()
+            .into_iter()
+            .collect(),
+        Err(errors) => errors,
+    }
+}
+
+/// Checks that `input` can be saved as the preset `name`, without using
+/// itself.
+pub(super) fn check_preset(
+    name: &str,
+    input: &str,
+    options: Options,
+    presets: &Presets,
+) -> Result<()> {
+    let expr = parse_inside(input, options, presets, &mut vec![name.to_string()])?;
+    eval(expr, Some(Duration::ZERO), Reference::current())?;
+    Ok(())
+}
+
+/// A preview of the timers an input would run, without starting them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The first segments of the input, up to the limit given.
+    pub segments: Vec<Segment>,
+    /// The total number of segments, or `None` if it repeats forever.
+    pub count: Option<u64>,
+    /// The duration of all segments together, or `None` if it repeats
+    /// forever or has random durations.
+    pub total: Option<Duration>,
+    /// When the last segment would finish if started now.
+    pub end_time: Option<OffsetDateTime>,
+    /// The time zone that the last segment is given in, if any, which the
+    /// end time can also be shown in.
+    pub zone: Option<Zone>,
+}
+
+/// Interprets `input` like [`interpret_multi`], but returns a summary of the
+/// timers instead of a plan.
+///
+/// Only the first `limit` segments are returned. Segments after that are
+/// still counted and added to the total. Segments are read using `options`,
+/// and `presets` can be used by name.
+///
+/// Specific times are relative to now, so the total may change between calls.
+///
+/// # Errors
+/// Errors if `input` is not a valid input for [`interpret_multi`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::{explain_multi, Options, Presets};
+/// use time::ext::NumericalDuration;
+///
+/// let (options, presets) = (Options::default(), Presets::default());
+/// let explanation = explain_multi("(25m + 5m) * 4", 3, options, &presets).unwrap();
+/// assert_eq!(explanation.segments.len(), 3);
+/// assert_eq!(explanation.count, Some(8));
+/// assert_eq!(explanation.total, Some(2.hours()));
+///
+/// assert_eq!(explain_multi("25m*", 3, options, &presets).unwrap().count, None);
+/// ```
+pub fn explain_multi(
+    input: &str,
+    limit: usize,
+    options: Options,
+    presets: &Presets,
+) -> Result<Explanation> {
+    let now = relative::now();
+    let plan = interpret_multi_with_presets(input, options, presets)?;
+    let total = plan.total();
+    let last = plan.len().and_then(|len| plan.get(len.checked_sub(1)?));
+
+    Ok(Explanation {
+        segments: (0..limit as u64).map_while(|i| plan.get(i)).collect(),
+        count: plan.len(),
+        total,
+        end_time: total.map(|total| now + total),
+        zone: last.and_then(|segment| segment.zone()),
+    })
+}
+
+/// Formats a multi-timer input in a canonical way, like "(25m + 5m) * 4".
+///
+/// Operators are surrounded by single spaces, implicit multiplication is
+/// written out, parentheses are only kept where they are needed and repeating
+/// forever is written as a trailing "*", or "forever" when it comes first.
+/// Each duration is written as it was entered, with any label before it, and
+/// each preset is replaced by its input.
+///
+/// Interpreting the output with the same `options` gives the same timers as
+/// interpreting `input`.
+///
+/// # Errors
+/// Errors if `input` is not a valid input for [`interpret_multi_with_presets`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::interpreter::{format_multi, Options, Presets};
+///
+/// let (options, presets) = (Options::default(), Presets::default());
+/// let format = |input| format_multi(input, options, &presets).unwrap();
+/// assert_eq!(format("(25m+5m)4"), "(25m + 5m) * 4");
+/// assert_eq!(format("2h + (1h*3)"), "2h + 1h * 3");
+/// assert_eq!(format("work: 25m*"), "work 25m*");
+/// ```
+pub fn format_multi(input: &str, options: Options, presets: &Presets) -> Result<String> {
+    let expr = parse_with(input, options, presets)?;
+    let formatted = expr.to_input();
+    // check that it evaluates too
+    eval(expr, Some(Duration::ZERO), Reference::current())?;
+    Ok(formatted)
 }
 
-pub struct InputIter {
-    iter: Peekable<Box<dyn ClonableIterator<Item = Arc<str>>>>,
+/// One timer in a sequence of timers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// A valid input for [`super::interpret_single`].
+    input: Arc<str>,
+    /// A name given to this segment, written before the duration.
+    label: Option<Arc<str>>,
+    /// What the duration of the input is multiplied by, like the "1.5" in
+    /// "1.5 * 20m".
+    scale: Option<f64>,
+    /// Time removed from the end of this segment by subtraction, after
+    /// scaling.
+    trim: Duration,
+    /// How long before the end to warn that the segment is finishing, like
+    /// the "@5m" in "20m @5m", along with each one as it was entered. Sorted
+    /// from longest to shortest.
+    warnings: Arc<[(Duration, Arc<str>)]>,
+    /// The range that the duration is picked from, if the input is a random
+    /// duration like "rand(3m, 7m)". The input is then only shown.
+    random: Option<Random>,
+    /// What the random duration is picked with, set by the [`Plan`] this
+    /// segment is from.
+    seed: u64,
+    /// How the input is interpreted.
+    options: Options,
 }
 
-impl InputIter {
-    pub fn empty() -> Self {
-        let iter: Box<dyn ClonableIterator<Item = Arc<str>>> = Box::new(iter::empty());
+impl Segment {
+    fn new(input: Arc<str>) -> Self {
         Self {
-            iter: iter.peekable(),
+            input,
+            label: None,
+            scale: None,
+            trim: Duration::ZERO,
+            warnings: Arc::from([]),
+            random: None,
+            seed: 0,
+            options: Options::default(),
+        }
+    }
+
+    /// Moves a random duration at the end of the input, like "rand(3m, 7m)"
+    /// or "~5m±1m", out of the input, with any words before it as the label.
+    ///
+    /// Returns whether there was a random duration, in which case the input
+    /// has no label to extract.
+    ///
+    /// # Errors
+    /// Errors if the random duration is not valid.
+    fn extract_random(&mut self) -> Result<bool> {
+        let Some(random) = random::find_random(&self.input, self.options) else {
+            return Ok(false);
+        };
+        let (start, random) = random?;
+
+        let label = self.input[..start].trim().trim_end_matches(':').trim_end();
+        if !label.is_empty() {
+            self.label = Some(Arc::from(label));
+        }
+        self.input = Arc::from(&self.input[start..]);
+        self.random = Some(random);
+        Ok(true)
+    }
+
+    /// Moves the warnings at the end of the input, like the "@5m @1m" in "20m
+    /// @5m @1m", out of the input.
+    ///
+    /// Returns each warning with its span in the input, for
+    /// [`Self::check_warnings`].
+    ///
+    /// # Errors
+    /// Errors if a warning is not a valid duration, is not positive, or is a
+    /// specific time.
+    fn extract_warnings(&mut self) -> Result<Vec<(Duration, Range<usize>)>> {
+        let ats = self.input.match_indices('@').map(|(i, _)| i).collect_vec();
+        let Some(&first) = ats.first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut warnings = Vec::with_capacity(ats.len());
+        let mut spans = Vec::with_capacity(ats.len());
+        for (i, &at) in ats.iter().enumerate() {
+            let end = ats.get(i + 1).copied().unwrap_or(self.input.len());
+            let warning = &self.input[at + 1..end];
+            let start = end - warning.trim_start().len();
+            let warning = warning.trim();
+            if warning.is_empty() {
+                return Err(ErrorKind::InvalidWarning.at(at..at + 1));
+            }
+
+            let duration =
+                interpret_single_with(warning, self.options).map_err(|e| e.offset(start))?;
+            let span = at..start + warning.len();
+            // a time like "5pm" is not a length of time before the end
+            if !duration.is_positive() || is_time(warning, self.options) {
+                return Err(ErrorKind::InvalidWarning.at(span));
+            }
+            warnings.push((duration, Arc::from(warning)));
+            spans.push((duration, span));
+        }
+
+        warnings.sort_by(|(a, _), (b, _)| b.cmp(a));
+        self.warnings = Arc::from(warnings);
+        self.input = Arc::from(self.input[..first].trim_end());
+        Ok(spans)
+    }
+
+    /// Checks that every warning in `spans`, from [`Self::extract_warnings`],
+    /// is shorter than the duration as it was entered, before any scaling or
+    /// trimming.
+    ///
+    /// Specific times like "5pm" are not checked, as their duration depends on
+    /// when the segment starts.
+    ///
+    /// # Errors
+    /// Errors if a warning is at least as long as the duration.
+    fn check_warnings(&self, spans: &[(Duration, Range<usize>)]) -> Result<()> {
+        if spans.is_empty() || is_time(&self.input, self.options) {
+            return Ok(());
+        }
+
+        let duration = interpret_single_with(&self.input, self.options)?;
+        match spans.iter().find(|(warning, _)| *warning >= duration) {
+            Some((_, span)) => Err(ErrorKind::InvalidWarning.at(span.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Validates the input, moving any leading words that are not part of the
+    /// duration into the label.
+    ///
+    /// For example, "intro 5m" becomes the input "5m" with label "intro". A
+    /// trailing ":" on the label is removed, so "intro: 5m" is the same. The
+    /// label cannot be a valid duration by itself.
+    ///
+    /// # Errors
+    /// Errors if no suffix of the input is a valid duration. The error is the
+    /// one from interpreting the whole input.
+    fn extract_label(&mut self) -> Result<()> {
+        let interpret = |input| interpret_single_with(input, self.options);
+        let Err(error) = interpret(&self.input) else {
+            return Ok(());
+        };
+
+        let split = self
+            .input
+            .match_indices(char::is_whitespace)
+            .map(|(i, _)| self.input.split_at(i))
+            // a label that is also a duration is more likely to be a mistake
+            .find(|(label, rest)| interpret(rest).is_ok() && interpret(label).is_err());
+
+        let Some((label, rest)) = split else {
+            return Err(error);
+        };
+
+        let label = label.trim().trim_end_matches(':').trim_end();
+        if !label.is_empty() {
+            self.label = Some(Arc::from(label));
         }
+        self.input = Arc::from(rest.trim());
+        Ok(())
     }
 
-    pub fn next(&mut self) -> Option<Arc<str>> {
-        self.iter.next()
+    /// Separates out the warnings, random duration and label of the input,
+    /// checking that the rest is a valid duration.
+    ///
+    /// # Errors
+    /// Errors if any part of the input is not valid, with the span in the
+    /// input. A single word is reported as an unknown preset if any `presets`
+    /// are defined, unless it looks more like a misspelt unit or keyword than
+    /// a misspelt preset name.
+    fn resolve(&mut self, presets: &Presets) -> Result<()> {
+        let name = self.input.to_lowercase();
+        let span = 0..self.input.len();
+        let warnings = self.extract_warnings()?;
+        if self.extract_random()? {
+            return Ok(());
+        }
+        self.extract_label().map_err(|e| {
+            if !presets::is_name(&name) || presets.iter().next().is_none() {
+                return e;
+            }
+            let near_preset = suggest::closest(&name, presets.iter().map(|(name, _)| name));
+            if near_preset.is_some() || e.suggestion().is_none() {
+                ErrorKind::UnknownPreset(name).at(span)
+            } else {
+                e
+            }
+        })?;
+        self.check_warnings(&warnings)
     }
 
-    pub fn peek(&mut self) -> Option<Arc<str>> {
-        self.iter.peek().cloned()
+    /// Evaluates the duration of this segment.
+    ///
+    /// Inputs that are a specific time are relative to now, so this may give
+    /// a different result each time it is called. Random durations are picked
+    /// using the seed of the plan, so are the same each time. The duration
+    /// saturates at 0 if the trim ends up being longer than the input.
+    ///
+    /// # Errors
+    /// Errors if the input is not a valid duration, or is too long once
+    /// scaled. This should not happen for segments created by
+    /// [`interpret_multi`], as they are validated first.
+    pub fn duration(&self) -> Result<Duration> {
+        self.duration_at(Reference::current())
     }
-}
 
-impl From<DurationsOrInt> for InputIter {
-    fn from(value: DurationsOrInt) -> Self {
-        let iter = match value {
-            DurationsOrInt::Durations(durations) => durations,
-            DurationsOrInt::Int(int) => Box::new(iter::once(Arc::from(int.to_string()))),
+    /// Evaluates the duration of this segment like [`Self::duration`], but
+    /// with specific times relative to `reference` instead of now.
+    ///
+    /// # Errors
+    /// Errors if the input is not valid, see [`Self::duration`].
+    pub(super) fn duration_at(&self, reference: Reference) -> Result<Duration> {
+        let duration = match self.random {
+            Some(random) => random.pick(self.seed),
+            None => interpret_single_at(&self.input, reference, self.options)?,
         };
-        Self {
-            iter: iter.peekable(),
+        let scaled = match self.scale {
+            Some(scale) => Duration::checked_seconds_f64(duration.as_seconds_f64() * scale)
+                .ok_or_else(|| ErrorKind::TooLong.at(0..self.input.len()))?,
+            None => duration,
+        };
+        Ok((scaled - self.trim).max(Duration::ZERO))
+    }
+
+    /// The name given to this segment, if any.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Whether the duration of this segment is picked at random, like
+    /// "rand(3m, 7m)".
+    pub const fn is_random(&self) -> bool {
+        self.random.is_some()
+    }
+
+    /// Sets what the random duration of this segment is picked with.
+    pub(super) const fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// How long before the end of this segment to warn that it is finishing,
+    /// from longest to shortest.
+    pub fn warnings(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.warnings.iter().map(|(duration, _)| *duration)
+    }
+
+    /// The time zone that this segment's time is given in, if any, like
+    /// "Europe/Berlin" in "9:30am Europe/Berlin".
+    pub fn zone(&self) -> Option<Zone> {
+        lexer::lex(&self.input, self.options.locale.vocabulary())
+            .ok()?
+            .into_iter()
+            .find(|group| group.variant == lexer::GroupKind::Zone)
+            .and_then(|group| zone::parse_zone(&group.string))
+    }
+
+    /// Removes more time from the end of this segment.
+    pub(super) fn add_trim(&mut self, trim: Duration) {
+        self.trim += trim;
+    }
+
+    /// Multiplies the duration of this segment by `factor`, including any
+    /// time already trimmed.
+    pub(super) fn scale(&mut self, factor: f64) {
+        self.scale = Some(self.scale.unwrap_or(1.0) * factor);
+        self.trim = Duration::saturating_seconds_f64(self.trim.as_seconds_f64() * factor);
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "{label} ")?;
         }
+        write!(f, "{}", self.input)?;
+        for (_, warning) in self.warnings.iter() {
+            write!(f, " @{warning}")?;
+        }
+        if let Some(scale) = self.scale {
+            write!(f, " * {scale}")?;
+        }
+        if !self.trim.is_zero() {
+            write!(f, " - {}", self.trim)?;
+        }
+        Ok(())
     }
 }
 
-fn eval(sexpr: SExpr) -> Result<DurationsOrInt> {
+/// Evaluates an expression into the timers it runs.
+///
+/// `start` is how long after the start of the whole input this expression
+/// starts, which is needed to know how many times "until" repeats. It is
+/// `None` where that is not known, like inside a repeated group. Both `start`
+/// and any specific times are relative to `reference`.
+fn eval(sexpr: SExpr, start: Option<Duration>, reference: Reference) -> Result<DurationsOrInt> {
+    let span = sexpr.span();
     match sexpr {
-        SExpr::Atom(value) => Ok(DurationsOrInt::from(value)),
+        SExpr::Atom(value, _) => Ok(DurationsOrInt::from(value)),
         SExpr::Cons(op, exprs) => {
             let [left, right] = *exprs;
-            let (left, right) = (eval(left)?, eval(right)?);
+            let left = eval(
+                left,
+                matches!(op, Op::Add | Op::Sub).then_some(start).flatten(),
+                reference,
+            )?;
+            let right_start = match (op, start) {
+                (Op::Add, Some(start)) => match left.total(reference) {
+                    Ok(total) => total.map(|total| start + total),
+                    // the start is only needed by "until", which errors then
+                    Err(ErrorKind::RandomLength) => None,
+                    Err(e) => return Err(e.at(span)),
+                },
+                _ => None,
+            };
+            let right = eval(right, right_start, reference)?;
             match op {
                 Op::Add => Ok(left.join(right)),
+                Op::Sub => left.subtract(right, reference).map_err(|e| e.at(span)),
+                Op::Until => left.until(right, start, reference).map_err(|e| e.at(span)),
                 Op::Mul => match (left, right) {
                     (DurationsOrInt::Durations(_), DurationsOrInt::Durations(_)) => {
-                        Err(Error::MulDurations)
+                        Err(ErrorKind::MulDurations.at(span))
+                    }
+                    (DurationsOrInt::Factor(l), DurationsOrInt::Factor(r)) => {
+                        Ok(DurationsOrInt::Factor(l.mul(&r)))
+                    }
+                    // found here when the factor is a group, like "(0.5 * 1.5) * inf"
+                    (DurationsOrInt::Int(FOREVER), DurationsOrInt::Factor(_))
+                    | (DurationsOrInt::Factor(_), DurationsOrInt::Int(FOREVER)) => {
+                        Err(ErrorKind::InvalidOp("forever".to_string()).at(span))
+                    }
+                    // ints are durations when next to a factor
+                    (d, DurationsOrInt::Factor(factor)) | (DurationsOrInt::Factor(factor), d) => {
+                        Ok(DurationsOrInt::Durations(d.into_node().scale(factor.value)))
                     }
                     (DurationsOrInt::Durations(d), DurationsOrInt::Int(int))
                     | (DurationsOrInt::Int(int), DurationsOrInt::Durations(d)) => {
-                        Ok(DurationsOrInt::durations(
-                            iter::repeat_n(d, int.saturating_as::<usize>()).flatten(),
-                        ))
+                        Ok(DurationsOrInt::Durations(Node::Repeat(Box::new(d), int)))
                     }
                     (DurationsOrInt::Int(l), DurationsOrInt::Int(r)) => {
-                        let l = Arc::from(l.to_string());
-                        Ok(DurationsOrInt::durations(iter::repeat_n(
-                            l,
-                            r.saturating_as::<usize>(),
+                        // "forever" is always the count, like in "forever * 2"
+                        let (int, count) = if l == FOREVER { (r, l) } else { (l, r) };
+                        let int = DurationsOrInt::Int(int).into_node();
+                        Ok(DurationsOrInt::Durations(Node::Repeat(
+                            Box::new(int),
+                            count,
                         )))
                     }
                 },
@@ -82,45 +640,120 @@ fn eval(sexpr: SExpr) -> Result<DurationsOrInt> {
     }
 }
 
-fn parse(input: &str) -> Result<SExpr> {
-    let mut lexer = Lexer::new(input);
+fn parse_with(input: &str, options: Options, presets: &Presets) -> Result<SExpr> {
+    parse_inside(input, options, presets, &mut Vec::new())
+}
 
-    // check that every Value::Duration is a valid duration expression too
-    for token in &lexer.tokens {
-        match token {
-            Token::Value(Value::Duration(str)) => _ = super::interpret_single(&str)?,
-            _ => (),
-        }
+/// Parses `input` like [`parse_with`], where `expanding` is the names of the
+/// presets that `input` is inside of, which it cannot use again.
+fn parse_inside(
+    input: &str,
+    options: Options,
+    presets: &Presets,
+    expanding: &mut Vec<String>,
+) -> Result<SExpr> {
+    parse_all(input, options, presets, expanding).map_err(|mut errors| errors.swap_remove(0))
+}
+
+/// Parses `input` like [`parse_inside`], but carries on past each error to
+/// find the rest of them too.
+///
+/// # Errors
+/// Errors with every error found, sorted by where they are in `input`. There
+/// is always at least one.
+fn parse_all(
+    input: &str,
+    options: Options,
+    presets: &Presets,
+    expanding: &mut Vec<String>,
+) -> Result<SExpr, Vec<Error>> {
+    let mut errors = Vec::new();
+    let mut lexer = Lexer::new(input, options.locale.vocabulary());
+    lexer.resolve_values(options, presets, expanding, &mut errors);
+
+    let mut result = expr_bp(&mut lexer, 0, &mut errors);
+    // only a ")" without a "(" can be left over, skip it and carry on
+    while let (token, span) = lexer.next()
+        && token != Token::Eof
+    {
+        errors.push(ErrorKind::UnbalancedParens.at(span));
+        result = expr_ops(&mut lexer, result, 0, &mut errors);
+    }
+    if let Some(result) = &result {
+        check_forevers(result, false, &mut errors);
     }
 
-    let result = expr_bp(&mut lexer, 0)?;
-    if lexer.peek() != Token::Eof {
-        Err(Error::UnbalancedParens)
-    } else {
-        Ok(result)
+    match result {
+        Some(result) if errors.is_empty() => Ok(result),
+        _ => {
+            errors.sort_by_key(|e| (e.span.start, e.span.end));
+            // a preset with errors gives them all the span of its name
+            errors.dedup();
+            Err(errors)
+        }
     }
 }
 
-fn expr_bp(lexer: &mut Lexer, min_bp: u8) -> Result<SExpr> {
-    let mut lhs = match lexer.next() {
-        Token::Op(Op::LParen) => {
-            let lhs = expr_bp(lexer, 0)?;
-            let next = lexer.next();
-            if next != Token::RPAREN {
-                return Err(Error::UnbalancedParens);
+/// Parses the expression at the start of `lexer`, with operators that bind at
+/// least as tightly as `min_bp`.
+///
+/// Errors are added to `errors`, skipping past the tokens that cause them
+/// where possible. Returns `None` if part of the expression is missing.
+fn expr_bp(lexer: &mut Lexer, min_bp: u8, errors: &mut Vec<Error>) -> Option<SExpr> {
+    let lhs = loop {
+        match lexer.peek() {
+            (Token::Op(Op::LParen), span) => {
+                lexer.next();
+                let lhs = expr_bp(lexer, 0, errors);
+                if lexer.peek().0 == Token::RPAREN {
+                    lexer.next();
+                } else {
+                    errors.push(ErrorKind::UnbalancedParens.at(span));
+                }
+                break lhs;
+            }
+            (Token::Value(val), span) => {
+                lexer.next();
+                break Some(SExpr::Atom(val, span));
+            }
+            // left for the "(" that it may close
+            (Token::Op(op @ Op::RParen), span) => {
+                errors.push(ErrorKind::InvalidOp(op.to_string()).at(span));
+                break None;
+            }
+            // skipped, there may be a value after it
+            (Token::Op(op), span) => {
+                lexer.next();
+                errors.push(ErrorKind::InvalidOp(op.to_string()).at(span));
+            }
+            (Token::Eof, span) => {
+                errors.push(ErrorKind::Empty.at(span));
+                break None;
             }
-            lhs
         }
-        Token::Value(val) => SExpr::Atom(val),
-        Token::Op(op) => return Err(Error::InvalidOp(op.to_string())),
-        Token::Eof => return Err(Error::Empty),
     };
 
+    expr_ops(lexer, lhs, min_bp, errors)
+}
+
+/// Parses the operators after `lhs` and what they apply to, like
+/// [`expr_bp`].
+fn expr_ops(
+    lexer: &mut Lexer,
+    mut lhs: Option<SExpr>,
+    min_bp: u8,
+    errors: &mut Vec<Error>,
+) -> Option<SExpr> {
     loop {
-        let op = match lexer.peek() {
-            Token::Eof => break,
-            Token::Op(op) => op,
-            t => return Err(Error::InvalidValue(t.to_string())),
+        let (op, op_span) = match lexer.peek() {
+            (Token::Eof, _) => break,
+            (Token::Op(op), span) => (op, span),
+            // skipped, there may be an operator after it
+            (t, span) => {
+                lexer.next();
+                errors.push(ErrorKind::InvalidValue(t.to_string()).at(span));
+                continue;
+            }
         };
 
         if let Some((l_bp, r_bp)) = infix_binding_power(op) {
@@ -129,12 +762,17 @@ fn expr_bp(lexer: &mut Lexer, min_bp: u8) -> Result<SExpr> {
             }
 
             lexer.next();
-            let rhs = match expr_bp(lexer, r_bp) {
-                Ok(rhs) => rhs,
-                Err(Error::Empty) => return Err(Error::InvalidOp(op.to_string())),
-                Err(e) => return Err(e),
-            };
-            lhs = SExpr::Cons(op, Box::new([lhs, rhs]));
+            let first_error = errors.len();
+            let rhs = expr_bp(lexer, r_bp, errors);
+            // nothing after the operator
+            if let Some(error) = errors.get_mut(first_error)
+                && error.kind == ErrorKind::Empty
+            {
+                *error = ErrorKind::InvalidOp(op.to_string()).at(op_span);
+            }
+            lhs = lhs
+                .zip(rhs)
+                .map(|exprs| SExpr::Cons(op, Box::new(exprs.into())));
 
             continue;
         }
@@ -142,35 +780,73 @@ fn expr_bp(lexer: &mut Lexer, min_bp: u8) -> Result<SExpr> {
         break;
     }
 
-    Ok(lhs)
+    lhs
+}
+
+/// Adds an error to `errors` for every "forever" in `expr` that is not the
+/// repeat count of a "*", like the "forever" in "5m + forever".
+///
+/// `is_count` is whether `expr` is in the position of a repeat count.
+fn check_forevers(expr: &SExpr, is_count: bool, errors: &mut Vec<Error>) {
+    let is_forever = |expr: &SExpr| matches!(expr, SExpr::Atom(Value::Int(FOREVER), _));
+    // a factor scales instead of repeating
+    let is_factor = |expr: &SExpr| matches!(expr, SExpr::Atom(Value::Factor(_), _));
+    match expr {
+        SExpr::Atom(Value::Int(FOREVER), span) if !is_count => {
+            errors.push(ErrorKind::InvalidOp("forever".to_string()).at(span.clone()));
+        }
+        SExpr::Atom(..) => {}
+        SExpr::Cons(op, exprs) => {
+            let [left, right] = &**exprs;
+            let is_mul = *op == Op::Mul;
+            // only one side can be the count of "forever * forever"
+            check_forevers(
+                left,
+                is_mul && !is_forever(right) && !is_factor(right),
+                errors,
+            );
+            check_forevers(right, is_mul && !is_factor(left), errors);
+        }
+    }
 }
 
 fn infix_binding_power(op: Op) -> Option<(u8, u8)> {
     Some(match op {
-        Op::Add => (1, 2),
-        Op::Mul => (3, 4),
+        Op::Add | Op::Sub => (1, 2),
+        Op::Mul | Op::Until => (3, 4),
         _ => return None,
     })
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 enum Token {
     Value(Value),
     Op(Op),
     Eof,
 }
 
-impl From<&str> for Token {
-    fn from(value: &str) -> Self {
+impl Token {
+    /// Parses a piece of the input, with numbers using the decimal separator
+    /// of `vocabulary`.
+    fn new(value: &str, vocabulary: &Vocabulary) -> Self {
         match value {
-            "+" => Self::Op(Op::Add),
-            "*" => Self::Op(Op::Mul),
+            "+" | "," | "then" => Self::Op(Op::Add),
+            "-" => Self::Op(Op::Sub),
+            "*" | "x" | "×" => Self::Op(Op::Mul),
             "(" => Self::Op(Op::LParen),
             ")" => Self::Op(Op::RParen),
+            "until" => Self::Op(Op::Until),
+            "inf" | "forever" => Self::FOREVER,
             "\0" => Self::Eof,
-            string => Self::Value(match string.parse::<u64>() {
-                Ok(int) => Value::Int(int),
-                Err(_) => Value::Duration(Arc::from(string)),
+            string => Self::Value(if let Ok(int) = string.parse::<u64>() {
+                Value::Int(int)
+            } else if let Some(value) = lexer::parse_number(string, vocabulary) {
+                Value::Factor(Factor {
+                    value,
+                    input: Arc::from(string.replace(vocabulary.decimal_separator, ".")),
+                })
+            } else {
+                Value::Duration(Segment::new(Arc::from(string)))
             }),
         }
     }
@@ -190,6 +866,7 @@ impl Token {
     const MUL: Self = Token::Op(Op::Mul);
     const LPAREN: Self = Token::Op(Op::LParen);
     const RPAREN: Self = Token::Op(Op::RParen);
+    const FOREVER: Self = Token::Value(Value::Int(FOREVER));
 
     /// Returns `true` if the token is [`Value`].
     ///
@@ -200,10 +877,11 @@ impl Token {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 enum Value {
-    Duration(Arc<str>),
+    Duration(Segment),
     Int(u64),
+    Factor(Factor),
 }
 
 impl fmt::Display for Value {
@@ -211,6 +889,30 @@ impl fmt::Display for Value {
         match self {
             Self::Duration(e) => write!(f, "{e}"),
             Self::Int(i) => write!(f, "{i}"),
+            Self::Factor(factor) => write!(f, "{}", factor.input),
+        }
+    }
+}
+
+/// A number that is not a whole number, like "1.5" or "1/2".
+///
+/// Multiplying by a factor scales durations instead of repeating them, so
+/// "1.5 * 20m" is one 30 minute timer. By itself it is a duration like any
+/// other number.
+#[derive(Debug, Clone, PartialEq)]
+struct Factor {
+    value: f64,
+    /// The number as it was entered, with "." as the decimal separator. A
+    /// valid input for [`super::interpret_single`].
+    input: Arc<str>,
+}
+
+impl Factor {
+    fn mul(&self, other: &Self) -> Self {
+        let value = self.value * other.value;
+        Self {
+            value,
+            input: Arc::from(value.to_string()),
         }
     }
 }
@@ -218,7 +920,10 @@ impl fmt::Display for Value {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Op {
     Add,
+    Sub,
     Mul,
+    /// Repeats the left side until the time on the right.
+    Until,
     LParen,
     RParen,
 }
@@ -227,7 +932,9 @@ impl fmt::Display for Op {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Op::Add => f.write_char('+'),
+            Op::Sub => f.write_char('-'),
             Op::Mul => f.write_char('*'),
+            Op::Until => f.write_str("until"),
             Op::LParen => f.write_char('('),
             Op::RParen => f.write_char(')'),
         }
@@ -235,67 +942,282 @@ impl fmt::Display for Op {
 }
 
 struct Lexer {
-    /// Stored in reverse order
-    tokens: Vec<Token>,
+    /// Tokens and their spans in the input, stored in reverse order
+    tokens: Vec<(Token, Range<usize>)>,
+    /// The length of the input, where [`Token::Eof`] is.
+    len: usize,
 }
 
 impl fmt::Debug for Lexer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_char('"')?;
-        f.write_str(&self.tokens.iter().rev().join(" "))?;
+        f.write_str(&self.tokens.iter().rev().map(|(t, _)| t).join(" "))?;
         f.write_char('"')
     }
 }
 
 impl Lexer {
-    pub fn new(input: &str) -> Self {
+    pub fn new(input: &str, vocabulary: &Vocabulary) -> Self {
         // end with eof, makes some stuff easier
-        let mut tokens = format!("{input}\0")
-            .split_inclusive(&['+', '*', '(', ')', '\0'])
-            .flat_map(|segment| <[&str; 2]>::from(segment.split_at(segment.len() - 1)))
-            .map(|s| s.trim())
-            .filter_map(|s| (!s.is_empty()).then(|| Token::from(s)))
+        let len = input.len();
+        let input = format!("{input}\0");
+        let mut tokens = split_inclusive_ops(&input, vocabulary)
+            .into_iter()
+            .flat_map(|(start, segment)| {
+                let op_len = segment.chars().next_back().map_or(0, char::len_utf8);
+                let (value, op) = segment.split_at(segment.len() - op_len);
+                [(start, value), (start + value.len(), op)]
+            })
+            .flat_map(|(start, s)| split_keywords(start, s))
+            .filter_map(|(start, s)| {
+                let start = start + (s.len() - s.trim_start().len());
+                let s = s.trim();
+                (!s.is_empty()).then(|| (Token::new(s, vocabulary), start..start + s.len()))
+            })
             // normalize input
             .tuple_windows()
-            .flat_map(|(curr, next)| {
-                // insert implicit multiply
+            .flat_map(|((curr, span), (next, _))| {
+                // inserted tokens have an empty span just after `curr`
+                let inserted_span = span.end..span.end;
+                // insert implicit multiply, values are only next to each
+                // other around keywords like "25m forever"
                 if (curr == Token::RPAREN && next.is_value())
-                    || (curr.is_value() && next == Token::LPAREN)
+                    || (curr.is_value() && (next == Token::LPAREN || next.is_value()))
                 {
-                    Either::Left([curr, Token::Op(Op::Mul)].into_iter())
+                    Either::Left([(curr, span), (Token::MUL, inserted_span)].into_iter())
                 } else if curr == Token::MUL && !(next == Token::LPAREN || next.is_value()) {
                     // postfix multiply
-                    Either::Left([curr, Token::Value(Value::Int(u64::MAX))].into_iter())
+                    Either::Left([(curr, span), (Token::FOREVER, inserted_span)].into_iter())
                 } else {
-                    Either::Right(iter::once(curr))
+                    Either::Right(iter::once((curr, span)))
                 }
             })
             .collect_vec();
 
         tokens.reverse();
-        Self { tokens }
+        Self { tokens, len }
+    }
+
+    /// Checks that every [`Value::Duration`] is a valid duration expression
+    /// too, separating out any warnings and labels, and replaces every preset
+    /// name with the tokens of its input in parentheses.
+    ///
+    /// The tokens from a preset all have the span of its name. `expanding` is
+    /// the names of the presets that the tokens are inside of, which cannot be
+    /// used again.
+    ///
+    /// Invalid values are added to `errors` and left as they are, so that the
+    /// rest of the input can still be parsed.
+    fn resolve_values(
+        &mut self,
+        options: Options,
+        presets: &Presets,
+        expanding: &mut Vec<String>,
+        errors: &mut Vec<Error>,
+    ) {
+        let mut tokens = Vec::with_capacity(self.tokens.len());
+        for (token, span) in std::mem::take(&mut self.tokens) {
+            let Token::Value(Value::Duration(mut segment)) = token else {
+                tokens.push((token, span));
+                continue;
+            };
+
+            let name = segment.input.to_lowercase();
+            if expanding.contains(&name) {
+                errors.push(ErrorKind::PresetCycle(name).at(span.clone()));
+                tokens.push((Token::Value(Value::Duration(segment)), span));
+                continue;
+            }
+            if let Some(input) = presets.get(&name) {
+                expanding.push(name);
+                let mut preset = Self::new(input, options.locale.vocabulary());
+                let mut preset_errors = Vec::new();
+                preset.resolve_values(options, presets, expanding, &mut preset_errors);
+                expanding.pop();
+
+                if !preset_errors.is_empty() {
+                    errors.extend(preset_errors.into_iter().map(|e| e.kind.at(span.clone())));
+                    tokens.push((Token::Value(Value::Duration(segment)), span));
+                    continue;
+                }
+
+                // tokens are in reverse order
+                tokens.push((Token::RPAREN, span.clone()));
+                tokens.extend(preset.tokens.into_iter().map(|(t, _)| (t, span.clone())));
+                tokens.push((Token::LPAREN, span));
+                continue;
+            }
+
+            segment.options = options;
+            if let Err(e) = segment.resolve(presets) {
+                errors.push(e.offset(span.start));
+            }
+            tokens.push((Token::Value(Value::Duration(segment)), span));
+        }
+        self.tokens = tokens;
+    }
+
+    pub fn next(&mut self) -> (Token, Range<usize>) {
+        self.tokens
+            .pop()
+            .unwrap_or((Token::Eof, self.len..self.len))
+    }
+
+    pub fn peek(&mut self) -> (Token, Range<usize>) {
+        self.tokens
+            .last()
+            .cloned()
+            .unwrap_or((Token::Eof, self.len..self.len))
     }
+}
+
+/// Splits the input after every operator, like `str::split_inclusive`, along
+/// with the byte index that each piece starts at.
+///
+/// The "-"s inside dates like "2026-12-24", the "+"s and "-"s inside ISO
+/// 8601 dates and times like "2026-10-16T17:00+02:00" and time zones like
+/// "UTC+2", and the decimal ","s inside ISO 8601 durations like "PT0,5S" are
+/// not treated as operators.
+///
+/// A "-" between two letters is part of a word, like "twenty-five" or
+/// "warm-up", so is not an operator either.
+///
+/// A "," is an operator, unless it is the decimal separator of `vocabulary`
+/// and is between two digits, like "1,5" in German.
+fn split_inclusive_ops<'a>(input: &'a str, vocabulary: &Vocabulary) -> Vec<(usize, &'a str)> {
+    const OPS: [char; 8] = ['+', '-', '*', '×', ',', '(', ')', '\0'];
 
-    pub fn next(&mut self) -> Token {
-        self.tokens.pop().unwrap_or(Token::Eof)
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = input.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        let prev_is_digit = input[..i].ends_with(|c: char| c.is_ascii_digit());
+        let prev_is_alphanumeric = input[..i].ends_with(|c: char| c.is_ascii_alphanumeric());
+        if !prev_is_digit
+            && let Some(len) =
+                iso::instant_len(&input[i..]).or_else(|| date::iso_date_len(&input[i..]))
+        {
+            // dates are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+        } else if !prev_is_alphanumeric && let Some(len) = iso::duration_len(&input[i..]) {
+            // durations are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+        } else if !prev_is_alphanumeric && let Some(len) = zone::zone_len(&input[i..]) {
+            // zones are all ascii, skip the rest of it
+            chars.by_ref().take(len - 1).for_each(drop);
+        } else if !prev_is_alphanumeric && let Some(len) = random::rand_len(&input[i..]) {
+            // the durations inside may not be ascii
+            let len = input[i..i + len].chars().count();
+            chars.by_ref().take(len - 1).for_each(drop);
+        } else if ch == vocabulary.decimal_separator
+            && prev_is_digit
+            && input[i + ch.len_utf8()..].starts_with(|c: char| c.is_ascii_digit())
+        {
+            // a decimal number, not an operator
+        } else if ch == '-'
+            && input[..i].ends_with(char::is_alphabetic)
+            && input[i + 1..].starts_with(char::is_alphabetic)
+        {
+            // a hyphenated word, not an operator
+        } else if OPS.contains(&ch) {
+            let end = i + ch.len_utf8();
+            pieces.push((start, &input[start..end]));
+            start = end;
+        }
+    }
+    if start < input.len() {
+        pieces.push((start, &input[start..]));
     }
+    pieces
+}
+
+/// Splits the keywords "until", "inf", "forever", "then" and "x" out of a
+/// piece of the input, so that they work without operators around them, like
+/// "25m until 5pm", along with the byte index that each piece starts at.
+///
+/// Keywords are only whole words, except that "x" can also be directly before
+/// a number, like "25m x4". Units and labels containing an "x" are left as
+/// they are.
+fn split_keywords(start: usize, piece: &str) -> Vec<(usize, &str)> {
+    const KEYWORDS: [&str; 5] = ["until", "inf", "forever", "then", "x"];
 
-    pub fn peek(&mut self) -> Token {
-        self.tokens.last().cloned().unwrap_or(Token::Eof)
+    let mut pieces = Vec::new();
+    let mut rest = 0;
+    let mut i = 0;
+    for word in piece.split_inclusive(char::is_whitespace) {
+        let trimmed = word.trim_end();
+        let is_repeat = trimmed
+            .strip_prefix('x')
+            .is_some_and(|count| !count.is_empty() && count.bytes().all(|b| b.is_ascii_digit()));
+        let keyword = if is_repeat { "x" } else { trimmed };
+        if KEYWORDS.contains(&keyword) {
+            pieces.push((start + rest, &piece[rest..i]));
+            pieces.push((start + i, keyword));
+            rest = i + keyword.len();
+        }
+        i += word.len();
     }
+    pieces.push((start + rest, &piece[rest..]));
+    pieces
 }
 
 /// An S-expression
 #[derive(Debug)]
 enum SExpr {
-    Atom(Value),
+    /// A value and its span in the input.
+    Atom(Value, Range<usize>),
     Cons(Op, Box<[SExpr; 2]>),
 }
 
+impl SExpr {
+    /// The range of the input this expression covers, from the start of its
+    /// first value to the end of its last.
+    fn span(&self) -> Range<usize> {
+        match self {
+            Self::Atom(_, span) => span.clone(),
+            Self::Cons(_, exprs) => exprs[0].span().start..exprs[1].span().end,
+        }
+    }
+
+    /// Formats this expression as an input that parses to the same expression.
+    ///
+    /// See [`format_multi`].
+    fn to_input(&self) -> String {
+        match self {
+            Self::Atom(Value::Int(FOREVER), _) => "forever".to_string(),
+            Self::Atom(value, _) => value.to_string(),
+            Self::Cons(op, exprs) => {
+                let [left, right] = exprs.as_ref();
+                let precedence = |e: &Self| match e {
+                    Self::Atom(..) => None,
+                    Self::Cons(op, _) => infix_binding_power(*op).map(|(l_bp, _)| l_bp),
+                };
+                let (op_precedence, _) =
+                    infix_binding_power(*op).expect("only infix operators are in a cons");
+
+                // operators are left associative, so the right side also needs
+                // parentheses for the same precedence
+                let left = match precedence(left) {
+                    Some(p) if p < op_precedence => format!("({})", left.to_input()),
+                    _ => left.to_input(),
+                };
+                if *op == Op::Mul && matches!(right, Self::Atom(Value::Int(FOREVER), _)) {
+                    return format!("{left}*");
+                }
+                let right = match precedence(right) {
+                    Some(p) if p <= op_precedence => format!("({})", right.to_input()),
+                    _ => right.to_input(),
+                };
+                format!("{left} {op} {right}")
+            }
+        }
+    }
+}
+
 impl fmt::Display for SExpr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SExpr::Atom(t) => write!(f, "{t}"),
+            SExpr::Atom(t, _) => write!(f, "{t}"),
             SExpr::Cons(head, rest) => {
                 write!(f, "({head}")?;
                 for s in rest.iter() {
@@ -307,49 +1229,109 @@ impl fmt::Display for SExpr {
     }
 }
 
-trait ClonableIterator: DynClone + Iterator {}
-impl<T> ClonableIterator for T where T: DynClone + Iterator {}
-dyn_clone::clone_trait_object!(<T> ClonableIterator<Item = T>);
-
 /// An evaluated duration expression.
 ///
 /// Any expressions that may still be interpreted as an integer will be the [`Int`]
 /// variant. Only when it must be interpreted as a duration will the integer become
-/// a [`Durations`] variant. All other values that cannot be interpreted as an
-/// integer will also be the [`Durations`] variant.
+/// a [`Durations`] variant. The same goes for [`Factor`] and non-whole numbers.
+/// All other values will be the [`Durations`] variant.
 ///
 /// [`Durations`]: DurationsOrInt::Durations
 /// [`Int`]: DurationsOrInt::Int
+/// [`Factor`]: DurationsOrInt::Factor
 #[derive(Clone)]
 enum DurationsOrInt {
-    Durations(Box<dyn ClonableIterator<Item = Arc<str>>>),
+    Durations(Node),
     Int(u64),
+    Factor(Factor),
 }
 
 impl DurationsOrInt {
-    pub fn durations(
-        it: impl IntoIterator<IntoIter: ClonableIterator, Item = Arc<str>> + 'static,
-    ) -> Self {
-        Self::Durations(Box::new(it.into_iter()))
+    pub fn into_node(self) -> Node {
+        match self {
+            DurationsOrInt::Durations(d) => d,
+            DurationsOrInt::Int(int) => Node::Segment(Segment::new(Arc::from(int.to_string()))),
+            DurationsOrInt::Factor(factor) => Node::Segment(Segment::new(factor.input)),
+        }
     }
 
-    pub fn into_durations(self) -> Box<dyn ClonableIterator<Item = Arc<str>>> {
+    /// The duration of every segment together, or `None` if it repeats
+    /// forever. Specific times are relative to `reference`.
+    ///
+    /// The error has no span, as it applies to the whole expression.
+    pub fn total(&self, reference: Reference) -> Result<Option<Duration>, ErrorKind> {
         match self {
-            DurationsOrInt::Durations(d) => d,
-            DurationsOrInt::Int(int) => Box::new(iter::once(Arc::from(int.to_string()))),
+            Self::Durations(d) => d.total(reference),
+            Self::Int(_) | Self::Factor(_) => self.clone().into_node().total(reference),
         }
     }
 
     pub fn join(self, other: Self) -> Self {
-        Self::durations(self.into_durations().chain(other.into_durations()))
+        Self::Durations(self.into_node().concat(other.into_node()))
+    }
+
+    /// Removes the total duration of `other` from the end of `self`.
+    ///
+    /// Trailing segments that are completely covered are dropped, and the
+    /// last remaining segment is shortened by whatever is left over. This
+    /// works on the whole tree, so long sequences don't need to be expanded.
+    /// Specific times are relative to `reference`.
+    ///
+    /// The error has no span, as it applies to the whole subtraction.
+    pub fn subtract(self, other: Self, reference: Reference) -> Result<Self, ErrorKind> {
+        let amount = other
+            .into_node()
+            .total(reference)?
+            .ok_or(ErrorKind::UnboundedSubtract)?;
+
+        let (node, remaining) = self.into_node().trim_end(amount, reference)?;
+        if remaining.is_positive() {
+            return Err(ErrorKind::NegativeDuration);
+        }
+        match node {
+            Some(node) if node.len() != Some(0) => Ok(Self::Durations(node)),
+            _ => Err(ErrorKind::EmptySequence),
+        }
+    }
+
+    /// Repeats `self` until the time given by `deadline`, cutting the last
+    /// segment short so that it ends exactly at that time.
+    ///
+    /// `start` is when `self` starts, relative to `reference`. A deadline
+    /// that is a duration instead of a time is also relative to `reference`.
+    ///
+    /// The error has no span, as it applies to the whole expression.
+    pub fn until(
+        self,
+        deadline: Self,
+        start: Option<Duration>,
+        reference: Reference,
+    ) -> Result<Self, ErrorKind> {
+        let deadline = deadline.into_node();
+        if deadline.len() != Some(1) {
+            return Err(ErrorKind::InvalidDeadline);
+        }
+        let deadline = deadline
+            .total(reference)?
+            .ok_or(ErrorKind::InvalidDeadline)?;
+        let start = start.ok_or(ErrorKind::UnknownStart)?;
+        let length = deadline - start;
+        if !length.is_positive() {
+            return Err(ErrorKind::InPast);
+        }
+
+        let repeated = Node::Repeat(Box::new(self.into_node()), FOREVER);
+        let (node, _) = repeated.take(length, reference)?;
+        node.map(Self::Durations).ok_or(ErrorKind::EmptySequence)
     }
 }
 
 impl From<Value> for DurationsOrInt {
     fn from(value: Value) -> Self {
         match value {
-            Value::Duration(d) => DurationsOrInt::durations(iter::once(d)),
+            Value::Duration(d) => DurationsOrInt::Durations(Node::Segment(d)),
             Value::Int(int) => DurationsOrInt::Int(int),
+            Value::Factor(factor) => DurationsOrInt::Factor(factor),
         }
     }
 }
@@ -357,6 +1339,24 @@ impl From<Value> for DurationsOrInt {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use time::ext::NumericalDuration;
+
+    use crate::{
+        interpreter::interpret_single,
+        time::{
+            locale::{Locale, ENGLISH, GERMAN},
+            meridiem::MeridiemPolicy,
+        },
+    };
+
+    fn parse(input: &str) -> Result<SExpr> {
+        parse_with(input, Options::default(), &Presets::default())
+    }
+
+    /// Formats `input` using the default options and no presets.
+    fn format(input: &str) -> Result<String> {
+        format_multi(input, Options::default(), &Presets::default())
+    }
 
     #[test]
     fn parse_valid() {
@@ -395,55 +1395,141 @@ mod tests {
             parse("(15*2m)*3+14d").unwrap().to_string(),
             "(+ (* (* 15 2m) 3) 14d)"
         );
+        assert_eq!(
+            parse("(25m + 5m) * 4 - 5m").unwrap().to_string(),
+            "(- (* (+ 25m 5m) 4) 5m)"
+        );
+        assert_eq!(
+            parse("1h - 10m + 5m").unwrap().to_string(),
+            "(+ (- 1h 10m) 5m)"
+        );
     }
 
     #[test]
-    fn parse_invalid() {
-        assert_eq!(parse("1h)").err(), Some(Error::UnbalancedParens));
-        assert_eq!(parse("(1h").err(), Some(Error::UnbalancedParens));
-        assert_eq!(parse("+30d").err(), Some(Error::InvalidOp("+".to_string())));
-        assert_eq!(parse("30d+").err(), Some(Error::InvalidOp("+".to_string())));
+    fn split_dates() {
         assert_eq!(
-            parse("12m+34d ++ 2h").err(),
-            Some(Error::InvalidOp("+".to_string()))
+            split_inclusive_ops("2026-12-24 18:00 - 5m+1h\0", &ENGLISH),
+            [(0, "2026-12-24 18:00 -"), (18, " 5m+"), (22, "1h\0")]
         );
+        assert_eq!(split_inclusive_ops("1-2", &ENGLISH), [(0, "1-"), (2, "2")]);
         assert_eq!(
-            parse("12m+34d +* 2h").err(),
-            Some(Error::InvalidOp("*".to_string()))
+            split_inclusive_ops("warm-up 5m-1m\0", &ENGLISH),
+            [(0, "warm-up 5m-"), (11, "1m\0")]
         );
         assert_eq!(
-            parse("1d+20(2+)").err(),
-            Some(Error::InvalidOp(")".to_string()))
+            split_inclusive_ops("1,5, 2,5m\0", &GERMAN),
+            [(0, "1,5,"), (4, " 2,5m\0")]
+        );
+        assert_eq!(
+            split_inclusive_ops("1,5\0", &ENGLISH),
+            [(0, "1,"), (2, "5\0")]
         );
-    }
-
-    impl InputIter {
-        fn collect(self) -> Vec<String> {
-            self.iter.map(|str| str.to_string()).collect_vec()
-        }
     }
 
     #[test]
-    fn eval_durations() {
+    fn parse_invalid() {
         assert_eq!(
-            interpret_multi("1 + 2 * 3").unwrap().collect(),
-            ["1", "2", "2", "2"]
+            parse("1h)").err(),
+            Some(ErrorKind::UnbalancedParens.at(2..3))
         );
-        assert_eq!(interpret_multi("1").unwrap().collect(), ["1"]);
         assert_eq!(
-            interpret_multi("2 + (3 + 4)").unwrap().collect(),
-            ["2", "3", "4"]
+            parse("(1h").err(),
+            Some(ErrorKind::UnbalancedParens.at(0..1))
         );
         assert_eq!(
-            interpret_multi("2h + (15m + 45)*3").unwrap().collect(),
-            ["2h", "15m", "45", "15m", "45", "15m", "45"]
+            parse("+30d").err(),
+            Some(ErrorKind::InvalidOp("+".to_string()).at(0..1))
         );
         assert_eq!(
-            interpret_multi("2h + 3*(15m + 45)").unwrap().collect(),
-            ["2h", "15m", "45", "15m", "45", "15m", "45"]
+            parse("30d+").err(),
+            Some(ErrorKind::InvalidOp("+".to_string()).at(3..4))
         );
         assert_eq!(
-            interpret_multi("2h + 3(15m + 45)").unwrap().collect(),
+            parse("12m+34d ++ 2h").err(),
+            Some(ErrorKind::InvalidOp("+".to_string()).at(9..10))
+        );
+        assert_eq!(
+            parse("12m+34d +* 2h").err(),
+            Some(ErrorKind::InvalidOp("*".to_string()).at(9..10))
+        );
+        assert_eq!(
+            parse("1d+20(2+)").err(),
+            Some(ErrorKind::InvalidOp(")".to_string()).at(8..9))
+        );
+    }
+
+    #[test]
+    fn diagnose() {
+        let diagnose = |input| diagnose_multi(input, Options::default(), &Presets::default());
+        assert_eq!(
+            diagnose("5m + * 3q + (1h"),
+            [
+                ErrorKind::InvalidOp("*".to_string()).at(5..6),
+                ErrorKind::InvalidUnit("q".to_string()).at(8..9),
+                ErrorKind::UnbalancedParens.at(12..13),
+            ]
+        );
+        assert_eq!(
+            diagnose("1h) + 2h)"),
+            [
+                ErrorKind::UnbalancedParens.at(2..3),
+                ErrorKind::UnbalancedParens.at(8..9),
+            ]
+        );
+        assert_eq!(
+            diagnose("2h + 5q +"),
+            [
+                ErrorKind::InvalidUnit("q".to_string()).at(6..7),
+                ErrorKind::InvalidOp("+".to_string()).at(8..9),
+            ]
+        );
+        // evaluated once it parses
+        assert_eq!(diagnose("5m * 5m"), [ErrorKind::MulDurations.at(0..7)]);
+        assert_eq!(diagnose("(25m + 5m) * 4"), []);
+
+        let presets: Presets = "pomo = 5q + 5q".parse().unwrap();
+        assert_eq!(
+            diagnose_multi("pomo + 1h + Pomo", Options::default(), &presets),
+            [
+                ErrorKind::InvalidUnit("q".to_string()).at(0..4),
+                ErrorKind::InvalidUnit("q".to_string()).at(12..16),
+            ]
+        );
+    }
+
+    impl Plan {
+        fn collect(mut self) -> Vec<String> {
+            iter::from_fn(|| self.next())
+                .map(|segment| segment.to_string())
+                .collect_vec()
+        }
+    }
+
+    fn segment(input: &str) -> Segment {
+        Segment::new(Arc::from(input))
+    }
+
+    #[test]
+    fn eval_durations() {
+        assert_eq!(
+            interpret_multi("1 + 2 * 3").unwrap().collect(),
+            ["1", "2", "2", "2"]
+        );
+        assert_eq!(interpret_multi("1").unwrap().collect(), ["1"]);
+        assert_eq!(
+            interpret_multi("2 + (3 + 4)").unwrap().collect(),
+            ["2", "3", "4"]
+        );
+        assert_eq!(
+            interpret_multi("2h + (15m + 45)*3").unwrap().collect(),
+            ["2h", "15m", "45", "15m", "45", "15m", "45"]
+        );
+        assert_eq!(
+            interpret_multi("2h + 3*(15m + 45)").unwrap().collect(),
+            ["2h", "15m", "45", "15m", "45", "15m", "45"]
+        );
+        assert_eq!(
+            interpret_multi("2h + 3(15m + 45)").unwrap().collect(),
             ["2h", "15m", "45", "15m", "45", "15m", "45"]
         );
         assert_eq!(
@@ -456,28 +1542,687 @@ mod tests {
         );
 
         let mut inps = interpret_multi("2h + 1h*").unwrap();
-        assert_eq!(inps.next(), Some(Arc::from("2h")));
-        assert_eq!(inps.next(), Some(Arc::from("1h")));
-        assert_eq!(inps.next(), Some(Arc::from("1h")));
-        assert_eq!(inps.next(), Some(Arc::from("1h")));
-        assert_eq!(inps.next(), Some(Arc::from("1h")));
+        assert_eq!(inps.next(), Some(segment("2h")));
+        assert_eq!(inps.next(), Some(segment("1h")));
+        assert_eq!(inps.next(), Some(segment("1h")));
+        assert_eq!(inps.next(), Some(segment("1h")));
+        assert_eq!(inps.next(), Some(segment("1h")));
 
         let mut inps = interpret_multi("2h + (15m + 45)*").unwrap();
-        assert_eq!(inps.next(), Some(Arc::from("2h")));
-        assert_eq!(inps.next(), Some(Arc::from("15m")));
-        assert_eq!(inps.next(), Some(Arc::from("45")));
-        assert_eq!(inps.next(), Some(Arc::from("15m")));
-        assert_eq!(inps.next(), Some(Arc::from("45")));
+        assert_eq!(inps.next(), Some(segment("2h")));
+        assert_eq!(inps.next(), Some(segment("15m")));
+        assert_eq!(inps.next(), Some(segment("45")));
+        assert_eq!(inps.next(), Some(segment("15m")));
+        assert_eq!(inps.next(), Some(segment("45")));
+    }
+
+    #[test]
+    fn eval_hyphenated_words() {
+        let plan = interpret_multi("twenty-five minutes + sit-ups 2m + warm-up: 5m").unwrap();
+        assert_eq!(plan.get(0).unwrap().duration(), Ok(25.minutes()));
+        assert_eq!(
+            plan.collect(),
+            ["twenty-five minutes", "sit-ups 2m", "warm-up 5m"]
+        );
+        assert_eq!(interpret_multi("5m-1m").unwrap().collect(), ["5m - 1m"]);
+    }
+
+    #[test]
+    fn eval_offsets() {
+        assert_eq!(
+            interpret_multi("10m before 3pm + 20m after 9am")
+                .unwrap()
+                .collect(),
+            ["10m before 3pm", "20m after 9am"]
+        );
+        // "+" and "-" join timers rather than offsetting a time
+        assert_eq!(
+            interpret_multi("3pm + 20m").unwrap().collect(),
+            ["3pm", "20m"]
+        );
+        assert_eq!(
+            interpret_multi("3pm - 10m").unwrap().collect(),
+            ["3pm - 10m"]
+        );
+    }
+
+    #[test]
+    fn eval_alternative_syntax() {
+        assert_eq!(
+            interpret_multi("25m, 5m, 25m").unwrap().collect(),
+            ["25m", "5m", "25m"]
+        );
+        assert_eq!(
+            interpret_multi("pt0,5s, 5m").unwrap().collect(),
+            ["pt0,5s", "5m"]
+        );
+        assert_eq!(
+            interpret_multi("PT0,5S + 5m").unwrap().collect(),
+            ["PT0,5S", "5m"]
+        );
+        assert_eq!(
+            interpret_multi("work 25m then rest 5m").unwrap().collect(),
+            ["work 25m", "rest 5m"]
+        );
+        assert_eq!(
+            interpret_multi("25m x3").unwrap().collect(),
+            ["25m", "25m", "25m"]
+        );
+        assert_eq!(
+            interpret_multi("(25m, 5m) x 2").unwrap().collect(),
+            ["25m", "5m", "25m", "5m"]
+        );
+        assert_eq!(
+            interpret_multi("2 × 25m then 1 hour x2").unwrap().collect(),
+            ["25m", "25m", "1 hour", "1 hour"]
+        );
+        assert_eq!(interpret_multi("25m×2").unwrap().len(), Some(2));
+        // only whole words are keywords
+        assert_eq!(
+            interpret_multi("box breathing 4m, six minutes")
+                .unwrap()
+                .collect(),
+            ["box breathing 4m", "six minutes"]
+        );
+
+        let options = Options {
+            locale: Locale::German,
+            ..Options::default()
+        };
+        let plan = interpret_multi_with("1,5 Std, 2,5, 5m", options).unwrap();
+        assert_eq!(plan.len(), Some(3));
+        assert_eq!(plan.total(), Some(1.hours() + 37.minutes() + 30.seconds()));
+
+        let options = Options {
+            locale: Locale::French,
+            ..Options::default()
+        };
+        let plan = interpret_multi_with("dix minutes x2", options).unwrap();
+        assert_eq!(plan.total(), Some(20.minutes()));
+
+        assert_eq!(format("25m, 5m x4").unwrap(), "25m + 5m * 4");
+        assert_eq!(
+            format("25m then x4").err(),
+            Some(ErrorKind::InvalidOp("*".to_string()).at(9..10))
+        );
+    }
+
+    #[test]
+    fn eval_warnings() {
+        let mut plan = interpret_multi("talk 20m @5m @1m + questions 10m").unwrap();
+        let talk = plan.next().unwrap();
+        assert_eq!(talk.label(), Some("talk"));
+        assert_eq!(talk.duration(), Ok(20.minutes()));
+        assert_eq!(talk.warnings().collect_vec(), [5.minutes(), 1.minutes()]);
+        assert_eq!(plan.next().unwrap().warnings().count(), 0);
+
+        assert_eq!(
+            interpret_multi("(25m @1m @ 5m + 5m) x2").unwrap().collect(),
+            ["25m @5m @1m", "5m", "25m @5m @1m", "5m"]
+        );
+        // warnings are before the end, however long the segment ends up
+        let segment = interpret_multi("1.5 * 20m @2 - 5m")
+            .unwrap()
+            .next()
+            .unwrap();
+        assert_eq!(segment.duration(), Ok(25.minutes()));
+        assert_eq!(segment.warnings().collect_vec(), [2.minutes()]);
+
+        assert_eq!(
+            format("intro: 5m @ 30 seconds").unwrap(),
+            "intro 5m @30 seconds"
+        );
+        assert_eq!(
+            interpret_multi("1h + 20m @").err(),
+            Some(ErrorKind::InvalidWarning.at(9..10))
+        );
+        assert_eq!(
+            interpret_multi("1h + 20m @0 @1m").err(),
+            Some(ErrorKind::InvalidWarning.at(9..11))
+        );
+        assert_eq!(
+            interpret_multi("1h + 20m @ 5a").err(),
+            Some(ErrorKind::InvalidUnit("a".to_string()).at(12..13))
+        );
+
+        // warnings must be before the start of a fixed duration
+        assert_eq!(
+            interpret_multi("1h + 5m @10m").err(),
+            Some(ErrorKind::InvalidWarning.at(8..12))
+        );
+        assert_eq!(
+            interpret_multi("break 5m @1m @5m").err(),
+            Some(ErrorKind::InvalidWarning.at(13..16))
+        );
+        assert!(interpret_multi("5m @1m").is_ok());
+        assert_eq!(
+            interpret_multi("20m @5pm").err(),
+            Some(ErrorKind::InvalidWarning.at(4..8))
+        );
+        assert_eq!(
+            interpret_multi("20m @1m @ 10m before 5pm").err(),
+            Some(ErrorKind::InvalidWarning.at(8..24))
+        );
+        assert!(interpret_multi("rand(3m,7m) @5m").is_ok());
+        assert!(interpret_multi("5pm @2h").is_ok());
+        assert!(interpret_multi("10m before 5pm @2h").is_ok());
+    }
+
+    #[test]
+    fn eval_presets() {
+        let presets: Presets = "pomo = (25m @1m + 5m) * 2\nlong = pomo + 15m\nloop = loop"
+            .parse()
+            .unwrap();
+        let interpret = |input| interpret_multi_with_presets(input, Options::default(), &presets);
+
+        assert_eq!(
+            interpret("long + 1h").unwrap().collect(),
+            ["25m @1m", "5m", "25m @1m", "5m", "15m", "1h"]
+        );
+        assert_eq!(interpret("2 * Pomo").unwrap().len(), Some(8));
+        assert_eq!(interpret("pomo*").unwrap().len(), None);
+        assert_eq!(interpret("pomo - 5m").unwrap().total(), Some(55.minutes()));
+
+        assert_eq!(
+            interpret("5m + pomdoro").err(),
+            Some(ErrorKind::UnknownPreset("pomdoro".to_string()).at(5..12))
+        );
+        assert_eq!(
+            interpret("intro + 5m").err(),
+            Some(ErrorKind::UnknownPreset("intro".to_string()).at(0..5))
+        );
+        // misspelt units and keywords still suggest a fix
+        assert_eq!(
+            interpret("5m + tomorow").unwrap_err().suggestion(),
+            Some("tomorrow")
+        );
+        assert_eq!(
+            interpret("5m + loop").err(),
+            Some(ErrorKind::PresetCycle("loop".to_string()).at(5..9))
+        );
+        // errors inside a preset are at its name
+        assert_eq!(
+            interpret("pomo * 2am").err(),
+            Some(ErrorKind::MulDurations.at(0..10))
+        );
+    }
+
+    #[test]
+    fn eval_random() {
+        let plan = interpret_multi("stretch: rand(30s, 1m) * 5 + 5m")
+            .unwrap()
+            .with_seed(7);
+        assert_eq!(plan.len(), Some(6));
+        assert_eq!(plan.total(), None);
+        let durations = (0..5)
+            .map(|i| plan.get(i).unwrap().duration().unwrap())
+            .collect_vec();
+        assert!(durations
+            .iter()
+            .all(|d| (30.seconds()..=1.minutes()).contains(d)));
+        // repeats are picked separately
+        assert!(durations.iter().any(|d| *d != durations[0]));
+        assert_eq!(plan.get(0).unwrap().label(), Some("stretch"));
+        assert_eq!(plan.get(5).unwrap().duration(), Ok(5.minutes()));
+
+        let other = interpret_multi("stretch: rand(30s, 1m) * 5 + 5m")
+            .unwrap()
+            .with_seed(8);
+        assert!((0..5).any(|i| other.get(i).unwrap().duration() != Ok(durations[i as usize])));
+
+        let segment = interpret_multi("drill ~5m±1m @1m").unwrap().next().unwrap();
+        assert!(segment.is_random());
+        assert_eq!(segment.to_string(), "drill ~5m±1m @1m");
+        assert_eq!(format("rand(3m,7m) x5").unwrap(), "rand(3m,7m) * 5");
+
+        assert_eq!(
+            interpret_multi("5m + rand(7m, 3m)").err(),
+            Some(ErrorKind::InvalidRandom.at(5..17))
+        );
+        assert_eq!(
+            interpret_multi("rand(3m, 7m) - 1m").err(),
+            Some(ErrorKind::RandomLength.at(0..17))
+        );
+        assert_eq!(
+            interpret_multi("rand(3m, 7m) + 5m until 1h").err(),
+            Some(ErrorKind::UnknownStart.at(15..26))
+        );
     }
 
     #[test]
     fn invalid_durations() {
         let inps = interpret_multi("2h + 4a + 3d");
-        assert_eq!(inps.err(), Some(Error::InvalidUnit("a".to_string())));
+        assert_eq!(
+            inps.err(),
+            Some(ErrorKind::InvalidUnit("a".to_string()).at(6..7))
+        );
         let inps = interpret_multi("(3h + 4 + (2ms 3)*2)*2");
-        assert_eq!(inps.err(), Some(Error::SmallerThanMilli(3.0)));
+        assert_eq!(
+            inps.err(),
+            Some(ErrorKind::SmallerThanMilli(3.0).at(15..16))
+        );
 
         let inps = interpret_multi("3h * 2am");
-        assert_eq!(inps.err(), Some(Error::MulDurations));
+        assert_eq!(inps.err(), Some(ErrorKind::MulDurations.at(0..8)));
+        let inps = interpret_multi("intro + 5m");
+        assert_eq!(
+            inps.err(),
+            Some(ErrorKind::InvalidUnit("intro".to_string()).at(0..5))
+        );
+
+        let error = interpret_multi("1h + 5 mni").err().unwrap();
+        assert_eq!(error.span, 7..10);
+        assert_eq!(
+            error.apply_suggestion("1h + 5 mni"),
+            Some("1h + 5 min".to_string())
+        );
+    }
+
+    #[test]
+    fn format_round_trip() {
+        let inputs = [
+            "1 + 2 * 3",
+            "1",
+            "2 + (3 + 4)",
+            "2h + (15m + 45)*3",
+            "2h + 3*(15m + 45)",
+            "2h + 3(15m + 45)",
+            "2h+2(15+45m)2+3h",
+            "(3*2m)*2+14d",
+            "(25m + 5m) * 4 - 5m",
+            "1h - 10m + 5m",
+            "1h - (5m + 5m)",
+            "2h + 1h*",
+            "2h + (15m + 45)*",
+            "3 * (2h*)",
+            "2099-12-24 18:00 - 5m + 1h",
+            "(work: 25m + rest 5) * 2 + long break 15m",
+            "(25m + 5m) forever",
+            "forever(1m + 1m)",
+            "10m + (25m + 5m) until 1h",
+            "1.5 * 20m + 1/2 * (10m + 2)",
+        ];
+
+        for input in inputs {
+            let formatted = format(input).unwrap();
+            assert_eq!(
+                parse(&formatted).unwrap().to_string(),
+                parse(input).unwrap().to_string(),
+                "{input} was formatted as {formatted}"
+            );
+        }
+
+        assert_eq!(
+            format("2h+2(15+45m)2+3h").unwrap(),
+            "2h + 2 * (15 + 45m) * 2 + 3h"
+        );
+        assert_eq!(format("1h - (5m + 5m)").unwrap(), "1h - (5m + 5m)");
+        assert_eq!(format("(1h - 5m) + 5m").unwrap(), "1h - 5m + 5m");
+        assert_eq!(format("3 * (2h*)").unwrap(), "3 * (2h*)");
+        assert_eq!(format("work 25m inf").unwrap(), "work 25m*");
+        assert_eq!(format("forever 1m").unwrap(), "forever * 1m");
+        assert_eq!(format("(25m+5m)until 5pm").unwrap(), "(25m + 5m) until 5pm");
+        assert_eq!(
+            format("10m - 1h").err().map(|e| e.kind),
+            Some(ErrorKind::NegativeDuration)
+        );
+    }
+
+    #[test]
+    fn format_with_options() {
+        let german = Options {
+            locale: Locale::German,
+            ..Options::default()
+        };
+        let presets: Presets = "pomo = (25m+5m) x2".parse().unwrap();
+        assert_eq!(
+            format_multi("1,5h + 2m", german, &presets).unwrap(),
+            "1,5h + 2m"
+        );
+        assert_eq!(
+            format_multi("1,5 Std x2", german, &presets).unwrap(),
+            "1,5 Std * 2"
+        );
+        assert_eq!(format("1,5h + 2m").unwrap(), "1 + 5h + 2m");
+
+        assert_eq!(
+            format_multi("pomo + 15m", german, &presets).unwrap(),
+            "(25m + 5m) * 2 + 15m"
+        );
+        assert_eq!(format("pomo + 15m").err().map(|e| e.span), Some(0..4));
+    }
+
+    #[test]
+    fn explain() {
+        let (options, presets) = (Options::default(), Presets::default());
+        let explanation = explain_multi("intro 5m + (25m + 5m) * 2", 2, options, &presets).unwrap();
+        assert_eq!(
+            explanation
+                .segments
+                .iter()
+                .map(ToString::to_string)
+                .collect_vec(),
+            ["intro 5m", "25m"]
+        );
+        assert_eq!(explanation.count, Some(5));
+        assert_eq!(explanation.total, Some(Duration::minutes(65)));
+        assert!(explanation.end_time.is_some());
+        assert_eq!(explanation.zone, None);
+
+        let explanation = explain_multi("25m + tomorrow 9am UTC+2", 1, options, &presets).unwrap();
+        assert_eq!(
+            explanation.zone.map(|zone| zone.to_string()).as_deref(),
+            Some("UTC+2")
+        );
+
+        let explanation = explain_multi("2h + 1h*", 10, options, &presets).unwrap();
+        assert_eq!(explanation.segments.len(), 10);
+        assert_eq!(explanation.count, None);
+        assert_eq!(explanation.end_time, None);
+
+        assert_eq!(
+            explain_multi("3h * 2am", 10, options, &presets)
+                .err()
+                .map(|e| e.kind),
+            Some(ErrorKind::MulDurations)
+        );
+    }
+
+    #[test]
+    fn eval_dates() {
+        assert_eq!(
+            interpret_multi("2099-12-24 18:00 - 5m + 1h")
+                .unwrap()
+                .collect(),
+            ["2099-12-24 18:00 - 5m", "1h"]
+        );
+        assert_eq!(
+            interpret_multi("2099-12-24T18:00+02:00 - 5m + PT1H")
+                .unwrap()
+                .collect(),
+            ["2099-12-24T18:00+02:00 - 5m", "PT1H"]
+        );
+        assert_eq!(
+            interpret_multi("5m + 2099-12-24 18:00 utc-3 + 1h")
+                .unwrap()
+                .collect(),
+            ["5m", "2099-12-24 18:00 utc-3", "1h"]
+        );
+        assert_eq!(
+            interpret_multi("5m + 10m before 2099-12-24 18:00 + 1h")
+                .unwrap()
+                .collect(),
+            ["5m", "10m before 2099-12-24 18:00", "1h"]
+        );
+    }
+
+    #[test]
+    fn eval_labels() {
+        let mut inps = interpret_multi("intro 5m + demo 20m + Q&A 10m").unwrap();
+        let intro = inps.next().unwrap();
+        assert_eq!(intro.label(), Some("intro"));
+        assert_eq!(intro.duration(), Ok(Duration::minutes(5)));
+        assert_eq!(inps.peek().unwrap().label(), Some("demo"));
+        assert_eq!(inps.next().unwrap().duration(), Ok(Duration::minutes(20)));
+        assert_eq!(inps.next().unwrap().label(), Some("Q&A"));
+        assert_eq!(inps.next(), None);
+
+        assert_eq!(
+            interpret_multi("(work: 25m + rest 5) * 2 + long break 15m")
+                .unwrap()
+                .collect(),
+            ["work 25m", "rest 5", "work 25m", "rest 5", "long break 15m"]
+        );
+        assert_eq!(
+            interpret_multi("3 hours").unwrap().next().unwrap().label(),
+            None
+        );
+        assert_eq!(
+            interpret_multi("intro 5m - 1m").unwrap().collect(),
+            ["intro 5m - 1m"]
+        );
+    }
+
+    #[test]
+    fn eval_subtraction() {
+        assert_eq!(interpret_multi("1h - 10m").unwrap().collect(), ["1h - 10m"]);
+        assert_eq!(
+            interpret_multi("1h - 10m")
+                .unwrap()
+                .next()
+                .unwrap()
+                .duration(),
+            Ok(Duration::minutes(50))
+        );
+        assert_eq!(
+            interpret_multi("(25m + 5m) * 2 - 5m").unwrap().collect(),
+            ["25m", "5m", "25m"]
+        );
+        assert_eq!(
+            interpret_multi("(25m + 5m) * 2 - 10m").unwrap().collect(),
+            ["25m", "5m", "25m - 5m"]
+        );
+        assert_eq!(
+            interpret_multi("10 + 20 - 5 + 3").unwrap().collect(),
+            ["10", "20 - 5m", "3"]
+        );
+        assert_eq!(
+            interpret_multi("1h - (5m + 5m)").unwrap().collect(),
+            ["1h - 10m"]
+        );
+
+        let plan = interpret_multi("(25m + 5m) * 1000000 - 10m").unwrap();
+        assert_eq!(plan.len(), Some(1_999_999));
+        assert_eq!(plan.get(1_999_998).unwrap().to_string(), "25m - 5m");
+        assert_eq!(plan.get(1_999_999), None);
+    }
+
+    #[test]
+    fn plan_indexing() {
+        let mut plan = interpret_multi("2h + (15m + 45) * 3").unwrap();
+        assert_eq!(plan.len(), Some(7));
+        assert_eq!(plan.total(), Some(Duration::minutes(300)));
+        assert_eq!(plan.get(0), Some(segment("2h")));
+        assert_eq!(plan.get(4), Some(segment("45")));
+        assert_eq!(plan.get(7), None);
+
+        plan.seek(5);
+        assert_eq!(plan.position(), 5);
+        assert_eq!(plan.next(), Some(segment("15m")));
+        assert_eq!(plan.next(), Some(segment("45")));
+        assert_eq!(plan.next(), None);
+
+        // only needs to look at the tree, not every segment
+        let mut plan = interpret_multi("(1m + 1m) * 10000").unwrap();
+        assert_eq!(plan.len(), Some(20000));
+        assert_eq!(plan.total(), Some(Duration::minutes(20000)));
+        plan.seek(5000);
+        assert_eq!(plan.next(), Some(segment("1m")));
+
+        let plan = interpret_multi("2h + 1h*").unwrap();
+        assert_eq!(plan.len(), None);
+        assert_eq!(plan.total(), None);
+        assert_eq!(plan.get(u64::MAX - 1), Some(segment("1h")));
+
+        let plan = interpret_multi("5m * 0 + 1m").unwrap();
+        assert_eq!(plan.len(), Some(1));
+        assert_eq!(plan.get(0), Some(segment("1m")));
+        assert!(Plan::empty().is_empty());
+    }
+
+    #[test]
+    fn invalid_subtraction() {
+        assert_eq!(
+            interpret_multi("10m - 1h").err(),
+            Some(ErrorKind::NegativeDuration.at(0..8))
+        );
+        assert_eq!(
+            interpret_multi("10m + 5m - 15m").err(),
+            Some(ErrorKind::EmptySequence.at(0..14))
+        );
+        assert_eq!(
+            interpret_multi("10m* - 5m").err(),
+            Some(ErrorKind::UnboundedSubtract.at(0..9))
+        );
+        assert_eq!(
+            parse("-5m").err(),
+            Some(ErrorKind::InvalidOp("-".to_string()).at(0..1))
+        );
+    }
+
+    #[test]
+    fn eval_forever() {
+        for input in [
+            "(25m + 5m) forever",
+            "(25m + 5m) * inf",
+            "forever(25m + 5m)",
+        ] {
+            let plan = interpret_multi(input).unwrap();
+            assert_eq!(plan.len(), None, "{input}");
+            assert_eq!(plan.get(1000), Some(segment("25m")), "{input}");
+            assert_eq!(plan.get(1001), Some(segment("5m")), "{input}");
+        }
+
+        let mut plan = interpret_multi("work 25m forever").unwrap();
+        assert_eq!(plan.next().unwrap().label(), Some("work"));
+        assert_eq!(plan.next().unwrap().label(), Some("work"));
+        assert_eq!(plan.len(), None);
+
+        let plan = interpret_multi("forever * 2").unwrap();
+        assert_eq!(plan.len(), None);
+        assert_eq!(plan.get(1000), Some(segment("2")));
+
+        // only a repeat count can be forever
+        let forever = |span| Some(ErrorKind::InvalidOp("forever".to_string()).at(span));
+        assert_eq!(interpret_multi("forever").err(), forever(0..7));
+        assert_eq!(interpret_multi("inf").err(), forever(0..3));
+        assert_eq!(interpret_multi("5m + forever").err(), forever(5..12));
+        assert_eq!(interpret_multi("1.5 * inf").err(), forever(6..9));
+        assert_eq!(interpret_multi("(0.5 * 1.5) * inf").err(), forever(1..17));
+        assert_eq!(interpret_multi("inf * inf").err(), forever(0..3));
+        assert_eq!(interpret_multi("25m until forever").err(), forever(10..17));
+    }
+
+    #[test]
+    fn eval_until() {
+        // a duration deadline is from the start of the input
+        assert_eq!(
+            interpret_multi("(25m + 5m) until 65m").unwrap().collect(),
+            ["25m", "5m", "25m", "5m", "25m - 20m"]
+        );
+        assert_eq!(
+            interpret_multi("10m + (25m + 5m) until 1h")
+                .unwrap()
+                .collect(),
+            ["10m", "25m", "5m", "25m - 5m"]
+        );
+        assert_eq!(
+            interpret_multi("work 20m until 1h").unwrap().collect(),
+            ["work 20m", "work 20m", "work 20m"]
+        );
+        assert_eq!(
+            interpret_multi("(25m + 5m) until 1h - 10m + 1m")
+                .unwrap()
+                .collect(),
+            ["25m", "5m", "25m - 5m", "1m"]
+        );
+
+        let plan = interpret_multi("(1m + 1m) until 2099-12-24 18:00").unwrap();
+        let total = plan.total().unwrap();
+        let deadline = interpret_single("2099-12-24 18:00").unwrap();
+        assert!((deadline - total).abs() < Duration::SECOND);
+        assert_eq!(plan.get(1000), Some(segment("1m")));
+    }
+
+    #[test]
+    fn with_options() {
+        let options = Options {
+            meridiem_policy: MeridiemPolicy::Strict24h,
+            ..Options::default()
+        };
+        let plan = interpret_multi_with("5m + wake 9:30", options).unwrap();
+        let segment = plan.get(1).unwrap();
+        assert_eq!(segment.label(), Some("wake"));
+
+        let expected = interpret_single("9:30am").unwrap();
+        assert!((segment.duration().unwrap() - expected).abs() < Duration::SECOND);
+
+        let options = Options {
+            locale: Locale::German,
+            ..Options::default()
+        };
+        let plan = interpret_multi_with("1,5 * 20m + 1,5 + pause 5 Min.", options).unwrap();
+        assert_eq!(plan.total(), Some(36.minutes() + 30.seconds()));
+        assert_eq!(plan.get(2).unwrap().label(), Some("pause"));
+    }
+
+    #[test]
+    fn invalid_until() {
+        assert_eq!(
+            interpret_multi("10m* + 5m until 1h").err(),
+            Some(ErrorKind::UnknownStart.at(7..18))
+        );
+        assert_eq!(
+            interpret_multi("(5m until 1h) * 2").err(),
+            Some(ErrorKind::UnknownStart.at(1..12))
+        );
+        assert_eq!(
+            interpret_multi("1h + 5m until 30m").err(),
+            Some(ErrorKind::InPast.at(5..17))
+        );
+        assert_eq!(
+            interpret_multi("5m until (1h + 2h)").err(),
+            Some(ErrorKind::InvalidDeadline.at(0..17))
+        );
+        assert_eq!(
+            interpret_multi("0 until 1h").err(),
+            Some(ErrorKind::UnreachableDeadline.at(0..10))
+        );
+        assert_eq!(
+            interpret_multi("25m until").err(),
+            Some(ErrorKind::InvalidOp("until".to_string()).at(4..9))
+        );
+    }
+
+    #[test]
+    fn eval_scaling() {
+        let mut plan = interpret_multi("1.5 * 20m").unwrap();
+        assert_eq!(plan.len(), Some(1));
+        assert_eq!(plan.next().unwrap().duration(), Ok(Duration::minutes(30)));
+
+        let plan = interpret_multi("(20m + 10) * 0.5").unwrap();
+        assert_eq!(plan.total(), Some(Duration::minutes(15)));
+        assert_eq!(plan.collect(), ["20m * 0.5", "10 * 0.5"]);
+
+        // whole numbers still repeat
+        assert_eq!(interpret_multi("2 * 20m").unwrap().len(), Some(2));
+        assert_eq!(
+            interpret_multi("1/2 * 1 1/2 * 1h").unwrap().total(),
+            Some(Duration::minutes(45))
+        );
+        assert_eq!(
+            interpret_multi("(25m + 5m) * 2 * 0.5").unwrap().total(),
+            Some(Duration::minutes(30))
+        );
+        assert_eq!(
+            interpret_multi("2.5").unwrap().total(),
+            Some(Duration::seconds(150))
+        );
+        assert_eq!(
+            interpret_multi("1 1/2 hours + 1/2h").unwrap().total(),
+            Some(Duration::hours(2))
+        );
+        assert_eq!(
+            interpret_multi("20m * 0.5 - 5m").unwrap().collect(),
+            ["20m * 0.5 - 5m"]
+        );
+        assert_eq!(
+            interpret_multi("(20m - 10m) * 0.5").unwrap().total(),
+            Some(Duration::minutes(5))
+        );
+        assert_eq!(
+            interpret_multi("2 * 0.5").unwrap().total(),
+            Some(Duration::minutes(1))
+        );
     }
 }
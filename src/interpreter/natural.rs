@@ -0,0 +1,374 @@
+use std::{fmt, ops::Range};
+
+use crate::time::locale::Vocabulary;
+
+use super::{
+    lexer::{Group, GroupKind},
+    ErrorKind, Result,
+};
+
+/// A group, with any natural language words recognised.
+#[derive(Debug, PartialEq, Clone)]
+enum WordKind {
+    Number(f64),
+    /// "a" or "an", which is only a number if a word comes after it.
+    Article(Group),
+    Fraction(f64),
+    And,
+    Of,
+    Past,
+    To,
+    Noon,
+    Midnight,
+    Other(Group),
+}
+
+impl fmt::Display for WordKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) | Self::Fraction(n) => write!(f, "{n}"),
+            Self::And => f.write_str("and"),
+            Self::Of => f.write_str("of"),
+            Self::Past => f.write_str("past"),
+            Self::To => f.write_str("to"),
+            Self::Noon => f.write_str("noon"),
+            Self::Midnight => f.write_str("midnight"),
+            Self::Article(group) | Self::Other(group) => f.write_str(&group.string),
+        }
+    }
+}
+
+/// A [`WordKind`] and the range of the input it covers, which may be several
+/// groups once words are combined.
+#[derive(Debug, PartialEq, Clone)]
+struct Word {
+    kind: WordKind,
+    span: Range<usize>,
+}
+
+impl Word {
+    const fn new(kind: WordKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl Word {
+    /// Recognises `group` as a word in `vocabulary`.
+    fn recognise(group: Group, vocabulary: &Vocabulary) -> Self {
+        let span = group.span.clone();
+        Self::new(WordKind::recognise(group, vocabulary), span)
+    }
+}
+
+impl WordKind {
+    /// Recognises `group` as a word in `vocabulary`, or [`WordKind::Other`] if
+    /// it is not a natural language word.
+    fn recognise(group: Group, vocabulary: &Vocabulary) -> Self {
+        if group.variant == GroupKind::Number
+            && let Ok(n) = group.string.parse::<f64>()
+        {
+            return Self::Number(n);
+        }
+        if group.variant != GroupKind::Text {
+            return Self::Other(group);
+        }
+
+        let string = group.string.as_str();
+        let find = |words: &[(&str, f64)]| {
+            words
+                .iter()
+                .find(|(word, _)| *word == string)
+                .map(|(_, n)| *n)
+        };
+        if vocabulary.articles.contains(&string) {
+            return Self::Article(group);
+        }
+        if let Some(n) = find(vocabulary.numbers) {
+            return Self::Number(n);
+        }
+        if let Some(n) = find(vocabulary.fractions) {
+            return Self::Fraction(n);
+        }
+        if vocabulary.and.contains(&string) {
+            return Self::And;
+        }
+
+        match string {
+            "of" => Self::Of,
+            "past" => Self::Past,
+            "to" => Self::To,
+            "noon" | "midday" => Self::Noon,
+            "midnight" => Self::Midnight,
+            _ => Self::Other(group),
+        }
+    }
+}
+
+/// Rewrites natural language phrases into groups that the parser accepts.
+///
+/// Recognises:
+/// - Number words, like "five" or "twenty five".
+/// - Fractions, like "a half", "half an hour" and "three quarters".
+/// - "and" between amounts, like "two and a half hours" or "an hour and a
+///   half" (a trailing fraction is of the unit before it).
+/// - Times relative to an hour, like "half past 3", "quarter to 5" or
+///   "10 past 9".
+/// - "noon" and "midnight".
+///
+/// Number words, articles, fractions and "and" are taken from `vocabulary`,
+/// while the rest are always in English.
+///
+/// Groups that are not part of a phrase are unchanged. New groups have the
+/// span of the whole phrase they came from.
+///
+/// # Errors
+/// Errors if a phrase is incomplete, like "half past" with no hour.
+pub(super) fn rewrite(groups: Vec<Group>, vocabulary: &Vocabulary) -> Result<Vec<Group>> {
+    let words = groups
+        .into_iter()
+        .map(|group| Word::recognise(group, vocabulary))
+        .collect();
+    let words = resolve_articles(words);
+    let words = combine_fractions(combine_tens(words));
+    let words = combine_and(words);
+    let kind = |i: usize| words.get(i).map(|w| &w.kind);
+
+    let mut output = Vec::with_capacity(words.len());
+    let mut i = 0;
+    while i < words.len() {
+        let span = words[i].span.clone();
+        match (&words[i].kind, kind(i + 1), kind(i + 2)) {
+            // "half past 3", "quarter to 5"
+            (WordKind::Number(mins), Some(relation @ (WordKind::Past | WordKind::To)), hour) => {
+                let Some(WordKind::Number(hour)) = hour else {
+                    return Err(ErrorKind::IncompletePhrase(relation.to_string())
+                        .at(words[i + 1].span.clone()));
+                };
+                let span = span.start..words[i + 2].span.end;
+                output.extend(relative_hour_time(
+                    *mins,
+                    *hour,
+                    relation == &WordKind::To,
+                    span,
+                )?);
+                i += 3;
+            }
+            // "an hour and a half"
+            (WordKind::Other(unit), Some(WordKind::And), Some(WordKind::Number(n)))
+                if *n < 1.0
+                    && vocabulary.unit(&unit.string).is_some()
+                    && !words.get(i + 3).is_some_and(is_text) =>
+            {
+                let fraction_span = words[i + 1].span.start..words[i + 2].span.end;
+                output.push(unit.clone());
+                output.push(number_group(*n, fraction_span.clone()));
+                output.push(Group {
+                    span: fraction_span,
+                    ..unit.clone()
+                });
+                i += 3;
+            }
+            (WordKind::Number(n), ..) => {
+                output.push(number_group(*n, span));
+                i += 1;
+            }
+            (WordKind::Noon | WordKind::Midnight, ..) => {
+                let meridiem = if words[i].kind == WordKind::Noon {
+                    vocabulary.pm[0]
+                } else {
+                    vocabulary.am[0]
+                };
+                output.extend([
+                    number_group(12.0, span.clone()),
+                    Group {
+                        variant: GroupKind::Separator,
+                        string: ":".to_string(),
+                        span: span.clone(),
+                    },
+                    Group {
+                        variant: GroupKind::Text,
+                        string: meridiem.to_string(),
+                        span,
+                    },
+                ]);
+                i += 1;
+            }
+            // "1 hour and 30 minutes"
+            (WordKind::And, ..) => i += 1,
+            (WordKind::Other(group), ..) => {
+                output.push(group.clone());
+                i += 1;
+            }
+            (
+                word @ (WordKind::Article(_)
+                | WordKind::Fraction(_)
+                | WordKind::Of
+                | WordKind::Past
+                | WordKind::To),
+                ..,
+            ) => {
+                return Err(ErrorKind::IncompletePhrase(word.to_string()).at(span));
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Turns articles like "a" and "an" into 1 if they are followed by a word, so that "an hour"
+/// works but "4a" is still an invalid unit.
+fn resolve_articles(words: Vec<Word>) -> Vec<Word> {
+    let mut output = Vec::with_capacity(words.len());
+    let mut words = words.into_iter().peekable();
+    while let Some(word) = words.next() {
+        if let WordKind::Article(group) = word.kind {
+            if words
+                .peek()
+                .is_some_and(|w| is_text(w) || matches!(w.kind, WordKind::Fraction(_)))
+            {
+                output.push(Word::new(WordKind::Number(1.0), word.span));
+            } else {
+                output.push(Word::new(WordKind::Other(group), word.span));
+            }
+        } else {
+            output.push(word);
+        }
+    }
+    output
+}
+
+/// Joins tens and ones, like "twenty five".
+fn combine_tens(words: Vec<Word>) -> Vec<Word> {
+    let mut output: Vec<Word> = Vec::with_capacity(words.len());
+    for word in words {
+        if let Some(last) = output.last_mut()
+            && let (WordKind::Number(tens), WordKind::Number(ones)) = (&mut last.kind, &word.kind)
+            && *tens >= 20.0
+            && *tens < 100.0
+            && *tens % 10.0 == 0.0
+            && *ones < 10.0
+            && ones.fract() == 0.0
+        {
+            *tens += ones;
+            last.span.end = word.span.end;
+        } else {
+            output.push(word);
+        }
+    }
+    output
+}
+
+/// Multiplies fractions with the numbers next to them, like "a half",
+/// "half an hour" or "three quarters of an hour".
+///
+/// Fractions by themselves become numbers, like "half hour".
+fn combine_fractions(words: Vec<Word>) -> Vec<Word> {
+    let mut output: Vec<Word> = Vec::with_capacity(words.len());
+    let mut words = words.into_iter().peekable();
+    while let Some(word) = words.next() {
+        let WordKind::Fraction(fraction) = word.kind else {
+            output.push(word);
+            continue;
+        };
+
+        let mut value = fraction;
+        let mut span = word.span;
+        if let Some(Word {
+            kind: WordKind::Number(n),
+            span: number_span,
+        }) = output.last()
+        {
+            value *= n;
+            span.start = number_span.start;
+            output.pop();
+        }
+
+        let of = words.next_if(|w| w.kind == WordKind::Of);
+        if let Some(Word {
+            kind: WordKind::Number(n),
+            span: number_span,
+        }) = words.next_if(|w| matches!(w.kind, WordKind::Number(_)))
+        {
+            value *= n;
+            output.push(Word::new(
+                WordKind::Number(value),
+                span.start..number_span.end,
+            ));
+        } else {
+            output.push(Word::new(WordKind::Number(value), span));
+            // leave the "of" to error later
+            output.extend(of);
+        }
+    }
+    output
+}
+
+/// Adds fractions to the number before them, like "two and a half".
+fn combine_and(words: Vec<Word>) -> Vec<Word> {
+    let mut output: Vec<Word> = Vec::with_capacity(words.len());
+    for word in words {
+        match (output.as_mut_slice(), &word.kind) {
+            (
+                [.., Word {
+                    kind: WordKind::Number(whole),
+                    span,
+                }, Word {
+                    kind: WordKind::And,
+                    ..
+                }],
+                WordKind::Number(f),
+            ) if *f < 1.0 => {
+                let number = Word::new(WordKind::Number(*whole + f), span.start..word.span.end);
+                output.pop();
+                output.pop();
+                output.push(number);
+            }
+            _ => output.push(word),
+        }
+    }
+    output
+}
+
+/// Creates groups for a time like "quarter to 5", all with the given span.
+///
+/// `mins` less than 1 is a fraction of an hour.
+fn relative_hour_time(mins: f64, hour: f64, to: bool, span: Range<usize>) -> Result<[Group; 3]> {
+    let mins = if mins < 1.0 { mins * 60.0 } else { mins };
+    if mins.fract() != 0.0 || hour.fract() != 0.0 || mins >= 60.0 {
+        return Err(ErrorKind::InvalidNumber(mins.to_string()).at(span));
+    }
+
+    let (hour, mins) = if to {
+        // "quarter to 1" is 12:45
+        let hour = match hour {
+            0.0 => 23.0,
+            1.0 => 12.0,
+            hour => hour - 1.0,
+        };
+        (hour, 60.0 - mins)
+    } else {
+        (hour, mins)
+    };
+
+    Ok([
+        number_group(hour, span.clone()),
+        Group {
+            variant: GroupKind::Separator,
+            string: ":".to_string(),
+            span: span.clone(),
+        },
+        number_group(mins, span),
+    ])
+}
+
+fn is_text(word: &Word) -> bool {
+    matches!(&word.kind, WordKind::Other(group) if group.variant == GroupKind::Text)
+}
+
+fn number_group(n: f64, span: Range<usize>) -> Group {
+    Group {
+        variant: GroupKind::Number,
+        string: n.to_string(),
+        span,
+    }
+}
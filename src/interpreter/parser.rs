@@ -1,27 +1,57 @@
-use crate::time::{meridiem::Meridiem, units::TimeUnit};
+use std::ops::Range;
+
+use crate::time::{
+    date::{self, TargetDate},
+    iso::{self, IsoDuration, IsoInstant},
+    locale::Vocabulary,
+    meridiem::Meridiem,
+    units::TimeUnit,
+    zone::{self, Zone},
+};
 
 use super::{
     lexer::{Group, GroupKind},
-    Error, Result,
+    ErrorKind, Result,
 };
 
 /// A valid token value.
 ///
 /// Guarantees:
 /// - Number is a valid float, not NaN or infinity.
-/// - Text is valid, either a time unit or meridiem.
+/// - Text is valid, either a time unit, meridiem, day, "at", "before" or
+///   "after".
+/// - Zone is a known time zone.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub(super) enum Token {
     Number(f64),
     Unit(TimeUnit),
     Meridiem(Meridiem),
     Separator,
+    /// Marks the input as a 24 hour time, like "at 17:30".
+    At,
+    Date(TargetDate),
+    IsoDuration(IsoDuration),
+    IsoInstant(IsoInstant),
+    /// The time zone that a time is in, like "europe/berlin".
+    Zone(Zone),
+    /// An offset before a time, like "10m before 3pm".
+    Before,
+    /// An offset after a time, like "20m after 9am".
+    After,
+    /// A time plus an offset, like "9am + 20m".
+    Plus,
+    /// A time minus an offset, like "3pm - 10m".
+    Minus,
 }
 
-impl TryFrom<Group> for Token {
-    type Error = Error;
+impl Token {
+    /// Whether this token offsets a time by a duration.
+    pub const fn is_offset(&self) -> bool {
+        matches!(self, Self::Before | Self::After | Self::Plus | Self::Minus)
+    }
 
-    fn try_from(value: Group) -> Result<Self> {
+    /// Parses a group into a token, with units and meridiems in `vocabulary`.
+    fn new(value: Group, vocabulary: &Vocabulary) -> Result<Self, ErrorKind> {
         let token = value.variant;
         let string = value.string;
         Ok(match token {
@@ -29,25 +59,69 @@ impl TryFrom<Group> for Token {
                 // TODO figure out how to remove the clone
                 let num = string
                     .parse::<f64>()
-                    .map_err(|_| Error::InvalidNumber(string.clone()))?;
+                    .map_err(|_| ErrorKind::InvalidNumber(string.clone()))?;
 
                 if num.is_nan() || num.is_infinite() {
-                    return Err(Error::InvalidNumber(string));
+                    return Err(ErrorKind::InvalidNumber(string));
                 }
                 Self::Number(num)
             }
             GroupKind::Text => {
-                if let Ok(n) = string.parse::<TimeUnit>() {
+                if string == "at" {
+                    Self::At
+                } else if string == "before" {
+                    Self::Before
+                } else if string == "after" {
+                    Self::After
+                } else if let Some(n) = vocabulary.unit(&string) {
                     Self::Unit(n)
+                } else if let Some(m) = vocabulary.meridiem(&string) {
+                    Self::Meridiem(m)
+                } else if let Ok(d) = string.parse::<TargetDate>() {
+                    Self::Date(d)
                 } else {
-                    Self::Meridiem(string.parse::<Meridiem>()?)
+                    // suggest from every kind of word, not just days
+                    let tokens = vocabulary
+                        .words()
+                        .chain(TargetDate::tokens())
+                        .chain(["at", "before", "after"]);
+                    return Err(ErrorKind::invalid_unit(&string, tokens));
                 }
             }
             GroupKind::Separator => Self::Separator,
+            GroupKind::Date => Self::Date(TargetDate::Date(
+                date::parse_iso_date(&string).ok_or(ErrorKind::InvalidDate(string))?,
+            )),
+            // a duration in the right format can still be too long
+            GroupKind::Iso if iso::duration_len(&string) == Some(string.len()) => {
+                Self::IsoDuration(iso::parse_duration(&string).ok_or(ErrorKind::TooLong)?)
+            }
+            GroupKind::Iso => {
+                Self::IsoInstant(iso::parse_instant(&string).ok_or(ErrorKind::InvalidDate(string))?)
+            }
+            GroupKind::Zone => {
+                Self::Zone(zone::parse_zone(&string).ok_or(ErrorKind::InvalidZone(string))?)
+            }
+            GroupKind::Sign if string == "+" => Self::Plus,
+            GroupKind::Sign => Self::Minus,
         })
     }
 }
 
-pub fn parse(groups: Vec<Group>) -> Result<Vec<Token>> {
-    groups.into_iter().map(Token::try_from).collect()
+/// Parses each group into a token, keeping the span of the group.
+///
+/// Units and meridiems are words in `vocabulary`.
+///
+/// # Errors
+/// Errors if any group is not a valid token.
+pub fn parse(groups: Vec<Group>, vocabulary: &Vocabulary) -> Result<Vec<(Token, Range<usize>)>> {
+    groups
+        .into_iter()
+        .map(|group| {
+            let span = group.span.clone();
+            Token::new(group, vocabulary)
+                .map(|token| (token, span.clone()))
+                .map_err(|e| e.at(span))
+        })
+        .collect()
 }
@@ -0,0 +1,422 @@
+use time::Duration;
+
+use super::{random, ErrorKind, Reference, Result, Segment};
+
+/// The number of repeats that means repeating forever, from a postfix "*".
+pub(super) const FOREVER: u64 = u64::MAX;
+
+/// An evaluated sequence of segments, kept as a tree so that it can be
+/// indexed without going through every segment.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Node {
+    Segment(Segment),
+    /// Each node one after another.
+    Concat(Vec<Self>),
+    /// The node repeated, forever if the count is [`FOREVER`].
+    Repeat(Box<Self>, u64),
+}
+
+impl Node {
+    /// Joins two nodes, flattening them if they are already joins.
+    pub fn concat(self, other: Self) -> Self {
+        let mut nodes = match self {
+            Self::Concat(nodes) => nodes,
+            node => vec![node],
+        };
+        match other {
+            Self::Concat(other) => nodes.extend(other),
+            node => nodes.push(node),
+        }
+        Self::Concat(nodes)
+    }
+
+    /// Multiplies the duration of every segment by `factor`.
+    pub fn scale(self, factor: f64) -> Self {
+        match self {
+            Self::Segment(mut segment) => {
+                segment.scale(factor);
+                Self::Segment(segment)
+            }
+            Self::Concat(nodes) => {
+                Self::Concat(nodes.into_iter().map(|node| node.scale(factor)).collect())
+            }
+            Self::Repeat(node, times) => Self::Repeat(Box::new(node.scale(factor)), times),
+        }
+    }
+
+    /// The number of segments, or `None` if it repeats forever or there are
+    /// too many to count.
+    pub fn len(&self) -> Option<u64> {
+        match self {
+            Self::Segment(_) => Some(1),
+            Self::Concat(nodes) => nodes
+                .iter()
+                .try_fold(0_u64, |total, node| total.checked_add(node.len()?)),
+            Self::Repeat(_, 0) => Some(0),
+            Self::Repeat(node, times) => match node.len()? {
+                0 => Some(0),
+                _ if *times == FOREVER => None,
+                len => len.checked_mul(*times),
+            },
+        }
+    }
+
+    /// Gets the segment at `index`, without going through the segments before
+    /// it.
+    pub fn get(&self, index: u64) -> Option<&Segment> {
+        match self {
+            Self::Segment(segment) => (index == 0).then_some(segment),
+            Self::Concat(nodes) => {
+                let mut index = index;
+                for node in nodes {
+                    match node.len() {
+                        Some(len) if index >= len => index -= len,
+                        // an unbounded node hides everything after it
+                        _ => return node.get(index),
+                    }
+                }
+                None
+            }
+            Self::Repeat(_, 0) => None,
+            Self::Repeat(node, _) => match node.len() {
+                Some(0) => None,
+                Some(len) => {
+                    if self.len().is_some_and(|total| index >= total) {
+                        return None;
+                    }
+                    node.get(index % len)
+                }
+                None => node.get(index),
+            },
+        }
+    }
+
+    /// The duration of every segment together, or `None` if it repeats
+    /// forever or is too long to fit in a [`Duration`]. Specific times are
+    /// relative to `reference`.
+    ///
+    /// # Errors
+    /// Errors if any segment cannot be evaluated, or has a random duration.
+    pub fn total(&self, reference: Reference) -> Result<Option<Duration>, ErrorKind> {
+        Ok(match self {
+            Self::Segment(segment) if segment.is_random() => return Err(ErrorKind::RandomLength),
+            Self::Segment(segment) => Some(segment.duration_at(reference).map_err(|e| e.kind)?),
+            Self::Concat(nodes) => {
+                let mut total = Some(Duration::ZERO);
+                for node in nodes {
+                    total = match (total, node.total(reference)?) {
+                        (Some(total), Some(duration)) => total.checked_add(duration),
+                        _ => None,
+                    };
+                }
+                total
+            }
+            Self::Repeat(_, 0) => Some(Duration::ZERO),
+            Self::Repeat(_, FOREVER) => None,
+            Self::Repeat(node, times) => {
+                node.total(reference)?.and_then(|d| checked_mul(d, *times))
+            }
+        })
+    }
+
+    /// Removes `amount` of time from the end of this node, with specific
+    /// times relative to `reference`.
+    ///
+    /// Trailing segments that are completely covered are dropped, and the
+    /// last remaining segment is shortened by whatever is left over. Whole
+    /// repeats are removed at once, so this does not depend on how many times
+    /// a node repeats.
+    ///
+    /// Returns what is left of the node (`None` if every segment was dropped)
+    /// and the amount of time that could not be removed.
+    ///
+    /// # Errors
+    /// Errors if the end of the node cannot be found as it repeats forever.
+    pub fn trim_end(
+        self,
+        amount: Duration,
+        reference: Reference,
+    ) -> Result<(Option<Self>, Duration), ErrorKind> {
+        if !amount.is_positive() {
+            return Ok((Some(self), amount));
+        }
+
+        match self {
+            Self::Segment(segment) if segment.is_random() => Err(ErrorKind::RandomLength),
+            Self::Segment(mut segment) => {
+                let duration = segment.duration_at(reference).map_err(|e| e.kind)?;
+                if duration <= amount {
+                    Ok((None, amount - duration))
+                } else {
+                    segment.add_trim(amount);
+                    Ok((Some(Self::Segment(segment)), Duration::ZERO))
+                }
+            }
+            Self::Concat(mut nodes) => {
+                let mut remaining = amount;
+                while remaining.is_positive()
+                    && let Some(last) = nodes.pop()
+                {
+                    let (kept, left) = last.trim_end(remaining, reference)?;
+                    nodes.extend(kept);
+                    remaining = left;
+                }
+                let node = (!nodes.is_empty()).then_some(Self::Concat(nodes));
+                Ok((node, remaining))
+            }
+            Self::Repeat(_, 0) => Ok((None, amount)),
+            Self::Repeat(_, FOREVER) => Err(ErrorKind::UnboundedSubtract),
+            Self::Repeat(node, times) => {
+                let once = node.total(reference)?.ok_or(ErrorKind::UnboundedSubtract)?;
+                if once.is_zero() {
+                    return Ok((None, amount));
+                }
+
+                // both are positive
+                let whole = whole_times(amount, once);
+                if whole >= times {
+                    let removed = checked_mul(once, times).ok_or(ErrorKind::UnboundedSubtract)?;
+                    return Ok((None, amount - removed));
+                }
+
+                let removed = checked_mul(once, whole).ok_or(ErrorKind::UnboundedSubtract)?;
+                let remaining = amount - removed;
+                let kept = times - whole;
+                if remaining.is_zero() {
+                    return Ok((Some(Self::Repeat(node, kept)), Duration::ZERO));
+                }
+
+                // the last repeat is shortened, which cannot be fully removed
+                // as `remaining` is less than one repeat
+                let (last, left) = node.as_ref().clone().trim_end(remaining, reference)?;
+                let node = match (kept - 1, last) {
+                    (0, last) => last,
+                    (kept, Some(last)) => Some(Self::Repeat(node, kept).concat(last)),
+                    (kept, None) => Some(Self::Repeat(node, kept)),
+                };
+                Ok((node, left))
+            }
+        }
+    }
+
+    /// Keeps only the first `amount` of time of this node, with specific
+    /// times relative to `reference`.
+    ///
+    /// Segments that start after `amount` are dropped, and the segment that
+    /// `amount` ends in is shortened to end exactly there. Whole repeats are
+    /// kept at once, so this also works on nodes that repeat forever.
+    ///
+    /// Returns what is kept of the node (`None` if nothing) and the amount of
+    /// time that is left over after the end of the node.
+    ///
+    /// # Errors
+    /// Errors if the node repeats forever without taking any time, so
+    /// `amount` is never reached.
+    pub fn take(
+        self,
+        amount: Duration,
+        reference: Reference,
+    ) -> Result<(Option<Self>, Duration), ErrorKind> {
+        if !amount.is_positive() {
+            return Ok((None, amount));
+        }
+
+        match self {
+            Self::Segment(segment) if segment.is_random() => Err(ErrorKind::RandomLength),
+            Self::Segment(mut segment) => {
+                let duration = segment.duration_at(reference).map_err(|e| e.kind)?;
+                if duration <= amount {
+                    Ok((Some(Self::Segment(segment)), amount - duration))
+                } else {
+                    segment.add_trim(duration - amount);
+                    Ok((Some(Self::Segment(segment)), Duration::ZERO))
+                }
+            }
+            Self::Concat(nodes) => {
+                let mut kept = Vec::new();
+                let mut remaining = amount;
+                for node in nodes {
+                    if !remaining.is_positive() {
+                        break;
+                    }
+                    let (node, left) = node.take(remaining, reference)?;
+                    kept.extend(node);
+                    remaining = left;
+                }
+                let node = (!kept.is_empty()).then_some(Self::Concat(kept));
+                Ok((node, remaining))
+            }
+            Self::Repeat(_, 0) => Ok((None, amount)),
+            Self::Repeat(node, times) => {
+                let Some(once) = node.total(reference)? else {
+                    // only the first repeat is ever reached
+                    return node.take(amount, reference);
+                };
+                if once.is_zero() {
+                    return if times == FOREVER {
+                        Err(ErrorKind::UnreachableDeadline)
+                    } else {
+                        Ok((Some(Self::Repeat(node, times)), amount))
+                    };
+                }
+
+                // both are positive
+                let whole = whole_times(amount, once);
+                if times != FOREVER && whole >= times {
+                    let used = checked_mul(once, times).ok_or(ErrorKind::UnboundedSubtract)?;
+                    return Ok((Some(Self::Repeat(node, times)), amount - used));
+                }
+
+                let used = checked_mul(once, whole).ok_or(ErrorKind::UnboundedSubtract)?;
+                let remaining = amount - used;
+                if remaining.is_zero() {
+                    return Ok((Some(Self::Repeat(node, whole)), Duration::ZERO));
+                }
+
+                // part of one more repeat, which is always fully used as
+                // `remaining` is less than one repeat
+                let (last, _) = node.as_ref().clone().take(remaining, reference)?;
+                let node = match (whole, last) {
+                    (0, last) => last,
+                    (whole, Some(last)) => Some(Self::Repeat(node, whole).concat(last)),
+                    (whole, None) => Some(Self::Repeat(node, whole)),
+                };
+                Ok((node, Duration::ZERO))
+            }
+        }
+    }
+}
+
+/// How many whole times `once` fits into `amount`, saturating at `u64::MAX`.
+fn whole_times(amount: Duration, once: Duration) -> u64 {
+    u64::try_from(amount.whole_nanoseconds() / once.whole_nanoseconds()).unwrap_or(u64::MAX)
+}
+
+/// Multiplies a duration by a large number, returning `None` on overflow.
+fn checked_mul(duration: Duration, times: u64) -> Option<Duration> {
+    let nanos = duration
+        .whole_nanoseconds()
+        .checked_mul(i128::from(times))?;
+    (nanos <= Duration::MAX.whole_nanoseconds()).then(|| Duration::nanoseconds_i128(nanos))
+}
+
+/// A sequence of segments from [`super::interpret_multi`].
+///
+/// Segments can be looked up by index without going through the segments
+/// before them, so the length, total duration and any segment are available
+/// straight away, even for long or infinite sequences.
+///
+/// The plan also keeps a position, which [`Plan::next`] moves forward.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    root: Node,
+    /// The index of the segment that `next` returns.
+    position: u64,
+    /// What random durations are picked with, see [`Plan::with_seed`].
+    seed: u64,
+}
+
+impl Plan {
+    pub(super) const fn new(root: Node) -> Self {
+        Self {
+            root,
+            position: 0,
+            seed: 0,
+        }
+    }
+
+    /// Uses `seed` to pick the durations of random segments, like "rand(3m,
+    /// 7m)".
+    ///
+    /// Each segment picks its own duration, so repeated random segments are
+    /// different from each other. The same seed always picks the same
+    /// durations, so a plan can be replayed exactly.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use minti_ui::interpreter::interpret_multi;
+    ///
+    /// let plan = interpret_multi("rand(3m, 7m) * 5").unwrap().with_seed(42);
+    /// let replayed = interpret_multi("rand(3m, 7m) * 5").unwrap().with_seed(42);
+    /// for i in 0..5 {
+    ///     assert_eq!(
+    ///         plan.get(i).unwrap().duration(),
+    ///         replayed.get(i).unwrap().duration()
+    ///     );
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// The seed that random durations are picked with.
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// A plan with no segments.
+    pub const fn empty() -> Self {
+        Self::new(Node::Concat(Vec::new()))
+    }
+
+    /// The number of segments, or `None` if it repeats forever.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use minti_ui::interpreter::interpret_multi;
+    ///
+    /// assert_eq!(interpret_multi("(25m + 5m) * 4").unwrap().len(), Some(8));
+    /// assert_eq!(interpret_multi("25m*").unwrap().len(), None);
+    /// ```
+    pub fn len(&self) -> Option<u64> {
+        self.root.len()
+    }
+
+    /// Whether there are no segments at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// The segment at `index`, regardless of the current position.
+    pub fn get(&self, index: u64) -> Option<Segment> {
+        let mut segment = self.root.get(index)?.clone();
+        if segment.is_random() {
+            segment.set_seed(random::mix(self.seed ^ random::mix(index)));
+        }
+        Some(segment)
+    }
+
+    /// The duration of every segment together, or `None` if it repeats
+    /// forever or has random durations.
+    ///
+    /// Specific times are relative to now, so this may change each time it is
+    /// called. Also `None` if a segment can no longer be evaluated, like a
+    /// time on a date that has now passed.
+    pub fn total(&self) -> Option<Duration> {
+        self.root.total(Reference::current()).ok().flatten()
+    }
+
+    /// The index of the segment that [`Plan::next`] will return.
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Moves the position so that [`Plan::next`] returns the segment at
+    /// `index`.
+    pub const fn seek(&mut self, index: u64) {
+        self.position = index;
+    }
+
+    /// Returns the segment at the current position and moves past it.
+    pub fn next(&mut self) -> Option<Segment> {
+        let segment = self.get(self.position)?;
+        self.position += 1;
+        Some(segment)
+    }
+
+    /// Returns the segment at the current position.
+    pub fn peek(&self) -> Option<Segment> {
+        self.get(self.position)
+    }
+}
@@ -0,0 +1,209 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use super::{interpret_single_with, multi, ErrorKind, Options, Result};
+
+/// Named inputs that can be used inside [`super::interpret_multi`] inputs,
+/// like "pomo" for "(25m + 5m) * 4 + 15m", so that "pomo * 2 + 1h" works.
+///
+/// Names are lowercase. They start with a letter, and only have letters,
+/// digits and "_"s.
+///
+/// Presets are stored one per line, like "pomo = (25m + 5m) * 4 + 15m".
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Presets(BTreeMap<String, String>);
+
+impl Presets {
+    /// The input for the preset called `name`, in any case.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(&name.to_lowercase()).map(String::as_str)
+    }
+
+    /// Every preset name and its input, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, input)| (name.as_str(), input.as_str()))
+    }
+
+    /// Saves the preset defined by `definition`, like "pomo = (25m + 5m) *
+    /// 4", replacing any preset with the same name. A definition with no
+    /// input, like "pomo =", removes the preset instead.
+    ///
+    /// The input is checked using `options` and the other presets.
+    ///
+    /// Returns `None` if `definition` is not a definition, so is an input to
+    /// interpret instead.
+    ///
+    /// # Errors
+    /// Errors if the name is already a duration or keyword, or the input is
+    /// not a valid input for [`super::interpret_multi`], including when it
+    /// uses itself. The presets are then left as they were.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use minti_ui::interpreter::{interpret_multi_with_presets, Options, Presets};
+    ///
+    /// let options = Options::default();
+    /// let mut presets = Presets::default();
+    /// presets.define("pomo = (25m + 5m) * 4", options).unwrap().unwrap();
+    ///
+    /// let plan = interpret_multi_with_presets("pomo * 2 + 1h", options, &presets);
+    /// assert_eq!(plan.unwrap().len(), Some(17));
+    /// assert!(presets.define("loop = loop + 5m", options).unwrap().is_err());
+    /// assert!(presets.define("25m + 5m", options).is_none());
+    /// ```
+    pub fn define(&mut self, definition: &str, options: Options) -> Option<Result<()>> {
+        let (name, input) = split_definition(definition)?;
+        let start = definition.len() - definition.trim_start().len();
+        let name_span = start..start + name.len();
+
+        let name = name.to_lowercase();
+        if KEYWORDS.contains(&name.as_str()) || interpret_single_with(&name, options).is_ok() {
+            return Some(Err(ErrorKind::InvalidPresetName(name).at(name_span)));
+        }
+
+        if input.trim().is_empty() {
+            log::debug!("removing preset {name}");
+            self.0.remove(&name);
+            return Some(Ok(()));
+        }
+
+        let input_start = definition.len() - input.len();
+        if let Err(e) = multi::check_preset(&name, input, options, self) {
+            return Some(Err(e.offset(input_start)));
+        }
+
+        log::debug!("saving preset {name} as {input}");
+        self.0.insert(name, input.trim().to_string());
+        Some(Ok(()))
+    }
+}
+
+/// Words that have a meaning in [`super::interpret_multi`], so cannot be
+/// preset names.
+const KEYWORDS: [&str; 5] = ["until", "inf", "forever", "then", "x"];
+
+/// Splits a preset definition like "pomo = 25m" into the name and input.
+///
+/// Returns `None` if it is not a definition.
+fn split_definition(definition: &str) -> Option<(&str, &str)> {
+    let (name, input) = definition.split_once('=')?;
+    let name = name.trim();
+    is_name(name).then_some((name, input))
+}
+
+/// Whether `s` can be the name of a preset, ignoring keywords and durations.
+pub(super) fn is_name(s: &str) -> bool {
+    s.starts_with(char::is_alphabetic) && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl fmt::Display for Presets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, input) in self.iter() {
+            writeln!(f, "{name} = {input}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Presets {
+    type Err = ErrorKind;
+
+    /// Parses presets stored by [`Presets::to_string`], which are not checked
+    /// again.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let (name, input) = split_definition(line)
+                    .ok_or_else(|| Self::Err::Other(format!("Invalid preset \"{line}\"")))?;
+                Ok((name.to_lowercase(), input.trim().to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn presets(definitions: &[&str]) -> Presets {
+        let mut presets = Presets::default();
+        for definition in definitions {
+            presets
+                .define(definition, Options::default())
+                .unwrap()
+                .unwrap();
+        }
+        presets
+    }
+
+    #[test]
+    fn define() {
+        let mut presets = presets(&["Pomo = (25m + 5m) * 4 + 15m", "short = 5m"]);
+        assert_eq!(presets.get("pomo"), Some("(25m + 5m) * 4 + 15m"));
+        assert_eq!(presets.get("POMO"), presets.get("pomo"));
+
+        let options = Options::default();
+        assert_eq!(presets.define("short =", options), Some(Ok(())));
+        assert_eq!(presets.get("short"), None);
+        assert_eq!(
+            presets
+                .define("long = short * 2", options)
+                .unwrap()
+                .err()
+                .map(|e| e.kind),
+            Some(ErrorKind::UnknownPreset("short".to_string()))
+        );
+        assert_eq!(presets.get("long"), None);
+
+        assert_eq!(presets.define("5m + 5m", options), None);
+        assert_eq!(presets.define("two words = 5m", options), None);
+        assert_eq!(
+            presets.define(" noon = 5m", options),
+            Some(Err(
+                ErrorKind::InvalidPresetName("noon".to_string()).at(1..5)
+            ))
+        );
+        assert_eq!(
+            presets.define("then = 5m", options),
+            Some(Err(
+                ErrorKind::InvalidPresetName("then".to_string()).at(0..4)
+            ))
+        );
+        assert_eq!(
+            presets.define("pomo = 5q", options),
+            Some(Err(ErrorKind::InvalidUnit("q".to_string()).at(8..9)))
+        );
+        assert_eq!(presets.get("pomo"), Some("(25m + 5m) * 4 + 15m"));
+    }
+
+    #[test]
+    fn cycles() {
+        let mut presets = presets(&["a = 5m", "b = a + 1m"]);
+        let options = Options::default();
+        assert_eq!(
+            presets.define("a = b", options),
+            Some(Err(ErrorKind::PresetCycle("a".to_string()).at(4..5)))
+        );
+        assert_eq!(
+            presets.define("c = c", options),
+            Some(Err(ErrorKind::PresetCycle("c".to_string()).at(4..5)))
+        );
+        assert_eq!(presets.get("a"), Some("5m"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let presets = presets(&["pomo = (25m + 5m) * 4", "break = 15m", "day = pomo + break"]);
+        let stored = presets.to_string();
+        assert_eq!(
+            stored,
+            "break = 15m\nday = pomo + break\npomo = (25m + 5m) * 4\n"
+        );
+        assert_eq!(stored.parse(), Ok(presets));
+        assert_eq!("".parse(), Ok(Presets::default()));
+        assert!("5m".parse::<Presets>().is_err());
+    }
+}
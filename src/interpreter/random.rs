@@ -0,0 +1,190 @@
+use std::ops::Range;
+
+use az::SaturatingAs;
+use time::Duration;
+
+use super::{interpret_single_with, ErrorKind, Options, Result};
+
+/// A range of durations that one is picked from at random, like
+/// "rand(3m, 7m)" or "~5m±1m".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Random {
+    min: Duration,
+    max: Duration,
+}
+
+impl Random {
+    /// Picks a duration in the range, to the nearest millisecond.
+    ///
+    /// The same `seed` always picks the same duration.
+    pub fn pick(self, seed: u64) -> Duration {
+        // min <= max is checked when parsing
+        let range = (self.max - self.min).whole_milliseconds() as u64;
+        let offset = mix(seed) % range.saturating_add(1);
+        self.min + Duration::milliseconds(offset.saturating_as())
+    }
+}
+
+/// Scrambles `seed`, so that seeds that are close together give unrelated
+/// numbers. This is the `SplitMix64` generator.
+pub(super) const fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Finds the length of the "rand(...)" at the start of `s`, if there is one.
+pub(super) fn rand_len(s: &str) -> Option<usize> {
+    if !s.starts_with("rand(") {
+        return None;
+    }
+    s.find(')').map(|end| end + 1)
+}
+
+/// Finds the random duration at the end of `input`, like "rand(3m, 7m)" or
+/// "~5m±1m", along with the byte index that it starts at.
+///
+/// "rand(a, b)" is anything from a to b, and "~a±b" is anything within b of
+/// a. The durations are read using `options`.
+///
+/// Returns `None` if there is no random duration.
+///
+/// # Errors
+/// Errors if the durations are not valid, the shortest is longer than the
+/// longest or it would be able to be negative.
+pub(super) fn find_random(input: &str, options: Options) -> Option<Result<(usize, Random)>> {
+    let is_word_start = |i: usize| i == 0 || input[..i].ends_with(char::is_whitespace);
+    if let Some(start) = input.find("rand(").filter(|&i| is_word_start(i)) {
+        return Some(parse_rand(input, start, options));
+    }
+    if let Some(start) = input.find('~').filter(|&i| is_word_start(i)) {
+        return Some(parse_around(input, start, options));
+    }
+    None
+}
+
+/// Parses a "rand(a, b)" that starts at `start` and goes to the end of
+/// `input`.
+fn parse_rand(input: &str, start: usize, options: Options) -> Result<(usize, Random)> {
+    let invalid = || ErrorKind::InvalidRandom.at(start..input.len());
+    let inner_start = start + "rand(".len();
+    let inner = input[inner_start..].strip_suffix(')').ok_or_else(invalid)?;
+    // a "," that is not followed by a space may be a decimal separator
+    let comma = inner
+        .find(", ")
+        .or_else(|| inner.find(','))
+        .ok_or_else(invalid)?;
+
+    let min = interpret_part(input, inner_start..inner_start + comma, options)?;
+    let max = interpret_part(
+        input,
+        inner_start + comma + 1..inner_start + inner.len(),
+        options,
+    )?;
+    if min > max {
+        return Err(invalid());
+    }
+    Ok((start, Random { min, max }))
+}
+
+/// Parses a "~a±b" that starts at `start` and goes to the end of `input`.
+fn parse_around(input: &str, start: usize, options: Options) -> Result<(usize, Random)> {
+    let invalid = || ErrorKind::InvalidRandom.at(start..input.len());
+    let center_start = start + '~'.len_utf8();
+    let (center, _) = input[center_start..].split_once('±').ok_or_else(invalid)?;
+    let spread_start = center_start + center.len() + '±'.len_utf8();
+
+    let center = interpret_part(input, center_start..spread_start - '±'.len_utf8(), options)?;
+    let spread = interpret_part(input, spread_start..input.len(), options)?;
+    if spread > center {
+        return Err(invalid());
+    }
+    Ok((
+        start,
+        Random {
+            min: center - spread,
+            max: center + spread,
+        },
+    ))
+}
+
+/// Interprets the part of `input` in `range` as a duration, with the error
+/// span in `input`.
+fn interpret_part(input: &str, range: Range<usize>, options: Options) -> Result<Duration> {
+    let part = &input[range.clone()];
+    let start = range.start + (part.len() - part.trim_start().len());
+    interpret_single_with(part.trim(), options).map_err(|e| e.offset(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::ext::NumericalDuration;
+
+    use super::*;
+
+    fn find(input: &str) -> Option<Result<(usize, Random)>> {
+        find_random(input, Options::default())
+    }
+
+    #[test]
+    fn parse() {
+        let random = |min, max| Random { min, max };
+        assert_eq!(
+            find("rand(3m, 7m)"),
+            Some(Ok((0, random(3.minutes(), 7.minutes()))))
+        );
+        assert_eq!(
+            find("stretch rand(30s,1m)"),
+            Some(Ok((8, random(30.seconds(), 1.minutes()))))
+        );
+        assert_eq!(
+            find("~5m±1m"),
+            Some(Ok((0, random(4.minutes(), 6.minutes()))))
+        );
+        assert_eq!(
+            find("drill ~ 1h ± 90s"),
+            Some(Ok((
+                6,
+                random(58.minutes() + 30.seconds(), 61.minutes() + 30.seconds())
+            )))
+        );
+        assert_eq!(find("5m"), None);
+        assert_eq!(find("operand(3m, 7m)"), None);
+
+        assert_eq!(
+            find("rand(7m, 3m)"),
+            Some(Err(ErrorKind::InvalidRandom.at(0..12)))
+        );
+        assert_eq!(
+            find("rand(3m)"),
+            Some(Err(ErrorKind::InvalidRandom.at(0..8)))
+        );
+        assert_eq!(find("~5m"), Some(Err(ErrorKind::InvalidRandom.at(0..3))));
+        assert_eq!(find("~1m±5m"), Some(Err(ErrorKind::InvalidRandom.at(0..7))));
+        assert_eq!(
+            find("rand(3m, 7q)"),
+            Some(Err(ErrorKind::InvalidUnit("q".to_string()).at(10..11)))
+        );
+    }
+
+    #[test]
+    fn pick() {
+        let random = Random {
+            min: 3.minutes(),
+            max: 7.minutes(),
+        };
+        for seed in 0..100 {
+            let duration = random.pick(seed);
+            assert!((3.minutes()..=7.minutes()).contains(&duration));
+            assert_eq!(random.pick(seed), duration);
+        }
+        assert_ne!(random.pick(1), random.pick(2));
+
+        let fixed = Random {
+            min: 5.minutes(),
+            max: 5.minutes(),
+        };
+        assert_eq!(fixed.pick(42), 5.minutes());
+    }
+}
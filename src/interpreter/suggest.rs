@@ -0,0 +1,81 @@
+/// Finds the candidate that `word` is most likely a typo of.
+///
+/// Candidates are compared by edit distance, where swapping two letters
+/// counts as one edit. Words up to 3 letters long may be 1 edit away, and
+/// longer words 2 edits. Ties go to the candidate closest in length, then
+/// to whichever comes first.
+///
+/// Returns `None` if no candidate is close enough.
+pub(super) fn closest<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = if word.chars().count() <= 3 { 1 } else { 2 };
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        // a single letter could be a typo of anything, so is never corrected
+        .filter(|&(distance, _)| distance <= max_distance && distance < word.len())
+        .min_by_key(|&(distance, candidate)| (distance, candidate.len().abs_diff(word.len())))
+        .map(|(_, candidate)| candidate)
+}
+
+/// The optimal string alignment distance between `a` and `b`.
+///
+/// This is the number of insertions, deletions, substitutions and swaps of
+/// adjacent characters to turn `a` into `b`, where no part is edited twice.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in distances[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("min", "min"), 0);
+        assert_eq!(edit_distance("mni", "min"), 1);
+        assert_eq!(edit_distance("hor", "hour"), 1);
+        assert_eq!(edit_distance("secnods", "seconds"), 1);
+        assert_eq!(edit_distance("", "day"), 3);
+        assert_eq!(edit_distance("abc", "ca"), 3);
+    }
+
+    #[test]
+    fn closest_candidates() {
+        let units = ["m", "min", "mins", "h", "hr", "hrs", "hour", "hours"];
+
+        assert_eq!(closest("mni", units), Some("min"));
+        assert_eq!(closest("hsr", units), Some("hrs"));
+        assert_eq!(closest("huors", units), Some("hours"));
+        assert_eq!(closest("x", units), None);
+        assert_eq!(closest("intro", units), None);
+    }
+}
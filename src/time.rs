@@ -1,4 +1,8 @@
+pub mod date;
+pub mod iso;
+pub mod locale;
 pub mod meridiem;
 pub mod relative;
 pub mod timestamp;
 pub mod units;
+pub mod zone;
@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use time::{Date, Month, Weekday};
+
+use crate::interpreter;
+
+/// A day that a specific time can be set on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TargetDate {
+    Today,
+    Tomorrow,
+    /// The next day that is this weekday, which may be today.
+    Weekday(Weekday),
+    /// An exact calendar date.
+    Date(Date),
+}
+
+impl TargetDate {
+    pub const TODAY_TOKENS: [&'static str; 1] = ["today"];
+    pub const TOMORROW_TOKENS: [&'static str; 3] = ["tomorrow", "tmr", "tmrw"];
+    pub const WEEKDAY_TOKENS: [(Weekday, &'static [&'static str]); 7] = [
+        (Weekday::Monday, &["mon", "monday"]),
+        (Weekday::Tuesday, &["tue", "tues", "tuesday"]),
+        (Weekday::Wednesday, &["wed", "weds", "wednesday"]),
+        (Weekday::Thursday, &["thu", "thur", "thurs", "thursday"]),
+        (Weekday::Friday, &["fri", "friday"]),
+        (Weekday::Saturday, &["sat", "saturday"]),
+        (Weekday::Sunday, &["sun", "sunday"]),
+    ];
+
+    /// Every token that is a day.
+    pub fn tokens() -> impl Iterator<Item = &'static str> {
+        Self::TODAY_TOKENS
+            .into_iter()
+            .chain(Self::TOMORROW_TOKENS)
+            .chain(
+                Self::WEEKDAY_TOKENS
+                    .into_iter()
+                    .flat_map(|(_, tokens)| tokens.iter().copied()),
+            )
+    }
+}
+
+impl FromStr for TargetDate {
+    type Err = interpreter::ErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if Self::TODAY_TOKENS.contains(&s) {
+            return Ok(Self::Today);
+        }
+        if Self::TOMORROW_TOKENS.contains(&s) {
+            return Ok(Self::Tomorrow);
+        }
+
+        Self::WEEKDAY_TOKENS
+            .iter()
+            .find(|(_, tokens)| tokens.contains(&s))
+            .map(|(weekday, _)| Self::Weekday(*weekday))
+            .ok_or_else(|| Self::Err::invalid_unit(s, Self::tokens()))
+    }
+}
+
+/// Parses an ISO 8601 calendar date, like "2026-12-24".
+///
+/// The month and day may be 1 or 2 digits long.
+///
+/// Returns `None` if the string is not in this format or is not a real date.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::date::parse_iso_date;
+/// use time::macros::date;
+///
+/// assert_eq!(parse_iso_date("2026-12-24"), Some(date!(2026-12-24)));
+/// assert_eq!(parse_iso_date("2026-1-2"), Some(date!(2026-01-02)));
+/// assert_eq!(parse_iso_date("2026-02-30"), None);
+/// ```
+pub fn parse_iso_date(s: &str) -> Option<Date> {
+    let mut parts = s.split('-');
+    let year = parts.next()?.parse::<i32>().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?;
+    let day = parts.next()?.parse::<u8>().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+/// Finds the length of the ISO 8601 date at the start of `s`, if there is one.
+///
+/// Only checks the shape `yyyy-m-d`, where the month and day are 1 or 2
+/// digits. The date is not checked to be valid.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::date::iso_date_len;
+///
+/// assert_eq!(iso_date_len("2026-12-24 18:00"), Some(10));
+/// assert_eq!(iso_date_len("2026-1-2"), Some(8));
+/// assert_eq!(iso_date_len("26-12-24"), None);
+/// assert_eq!(iso_date_len("2026-12-245"), None);
+/// ```
+pub fn iso_date_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let digits_from = |start: usize| {
+        bytes.get(start..).map_or(0, |rest| {
+            rest.iter().take_while(|b| b.is_ascii_digit()).count()
+        })
+    };
+
+    if digits_from(0) != 4 || bytes.get(4) != Some(&b'-') {
+        return None;
+    }
+    let month_len = digits_from(5);
+    if !(1..=2).contains(&month_len) || bytes.get(5 + month_len) != Some(&b'-') {
+        return None;
+    }
+    let day_start = 6 + month_len;
+    let day_len = digits_from(day_start);
+    if !(1..=2).contains(&day_len) {
+        return None;
+    }
+
+    Some(day_start + day_len)
+}
@@ -0,0 +1,405 @@
+use std::fmt::Write;
+
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use super::{date, units::TimeUnit, zone::Zone};
+
+/// An ISO 8601 duration like "P1Y2M3DT4H", split into the calendar months
+/// and everything else.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IsoDuration {
+    /// Whole years and months, which depend on the calendar.
+    pub months: i64,
+    /// Everything other than [`IsoDuration::months`], including any fraction
+    /// of a year or month.
+    pub duration: Duration,
+}
+
+/// An ISO 8601 date and time like "2026-10-16T17:00:00+02:00", where the
+/// offset may be left out.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IsoInstant {
+    /// The date and time as written.
+    pub datetime: PrimitiveDateTime,
+    /// The offset from UTC, or `None` if it is in the local time zone.
+    pub offset: Option<UtcOffset>,
+}
+
+impl IsoInstant {
+    /// The instant that this is, using `zone` if no offset was given.
+    pub fn resolve(self, zone: Zone) -> OffsetDateTime {
+        match self.offset {
+            Some(offset) => self.datetime.assume_offset(offset),
+            None => zone.resolve(self.datetime),
+        }
+    }
+}
+
+/// Parses an ISO 8601 duration, like "PT1H30M" or "P2DT3H".
+///
+/// Years, months, weeks and days come after the "P", and hours, minutes and
+/// seconds after the "T". Any of them may be left out, but they must be in
+/// that order and there must be at least one. Any number may have a decimal
+/// part, with a "." or ",". Letters may be either case.
+///
+/// Returns `None` if the string is not in this format, or is too long to be
+/// a [`Duration`].
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::{parse_duration, IsoDuration};
+/// use time::ext::NumericalDuration;
+///
+/// assert_eq!(
+///     parse_duration("PT1H30M"),
+///     Some(IsoDuration { months: 0, duration: 90.minutes() })
+/// );
+/// assert_eq!(
+///     parse_duration("p1y2dt0.5s"),
+///     Some(IsoDuration { months: 12, duration: 2.days() + 500.milliseconds() })
+/// );
+/// assert_eq!(parse_duration("PT"), None);
+/// assert_eq!(parse_duration("P1H"), None);
+/// assert_eq!(parse_duration("PT99999999999999999999H"), None);
+/// assert_eq!(parse_duration("P99999999999999999999Y"), None);
+/// ```
+pub fn parse_duration(s: &str) -> Option<IsoDuration> {
+    let mut result = IsoDuration {
+        months: 0,
+        duration: Duration::ZERO,
+    };
+    for (value, unit) in duration_parts(s)? {
+        let value = match unit.calendar_months() {
+            Some(months) => {
+                let whole = value.trunc();
+                result.months = result
+                    .months
+                    .checked_add((whole as i64).checked_mul(months)?)?;
+                value - whole
+            }
+            None => value,
+        };
+        result.duration = result
+            .duration
+            .checked_add(unit.checked_to_duration(value)?)?;
+    }
+    Some(result)
+}
+
+/// Splits an ISO 8601 duration into each number and its unit, checking the
+/// format like [`parse_duration`] but not the size.
+///
+/// Returns `None` if the string is not in this format.
+fn duration_parts(s: &str) -> Option<Vec<(f64, TimeUnit)>> {
+    const DATE_UNITS: [(u8, TimeUnit); 4] = [
+        (b'y', TimeUnit::Year),
+        (b'm', TimeUnit::Month),
+        (b'w', TimeUnit::Week),
+        (b'd', TimeUnit::Day),
+    ];
+    const TIME_UNITS: [(u8, TimeUnit); 3] = [
+        (b'h', TimeUnit::Hour),
+        (b'm', TimeUnit::Min),
+        (b's', TimeUnit::Sec),
+    ];
+
+    let s = s.to_ascii_lowercase();
+    let rest = s.strip_prefix('p')?;
+    let (date_part, time_part) = match rest.split_once('t') {
+        Some((_, "")) => return None,
+        Some((date_part, time_part)) => (date_part, Some(time_part)),
+        None => (rest, None),
+    };
+
+    let mut parts = Vec::new();
+    for (part, units) in [
+        (date_part, &DATE_UNITS[..]),
+        (time_part.unwrap_or(""), &TIME_UNITS),
+    ] {
+        let mut units = units.iter();
+        let mut rest = part;
+        while !rest.is_empty() {
+            let number_len = rest
+                .bytes()
+                .take_while(|b| b.is_ascii_digit() || *b == b'.' || *b == b',')
+                .count();
+            let (number, designator) = rest.split_at(number_len);
+            let designator = *designator.as_bytes().first()?;
+            // only allows units after the previous one
+            let (_, unit) = units.find(|(d, _)| *d == designator)?;
+            let value = number.replace(',', ".").parse::<f64>().ok()?;
+
+            parts.push((value, *unit));
+            rest = &rest[number_len + 1..];
+        }
+    }
+
+    (!parts.is_empty()).then_some(parts)
+}
+
+/// Finds the length of the ISO 8601 duration at the start of `s`, if there is
+/// one.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::duration_len;
+///
+/// assert_eq!(duration_len("PT25M + PT5M"), Some(5));
+/// assert_eq!(duration_len("pt0,5s, 5m"), Some(6));
+/// assert_eq!(duration_len("pm"), None);
+/// ```
+pub fn duration_len(s: &str) -> Option<usize> {
+    let len = s
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'.' || *b == b',')
+        .count();
+    // a duration always ends with a unit, not a separator after it
+    let len = s[..len].trim_end_matches(['.', ',']).len();
+    duration_parts(&s[..len]).map(|_| len)
+}
+
+/// Parses an ISO 8601 date and time, like "2026-10-16T17:00:00+02:00".
+///
+/// The seconds are optional and may have a decimal part. The offset may be
+/// "Z" for UTC, or like "+02:00", "+0200" or "+02". Without an offset, the
+/// time is in the local time zone, which is left for
+/// [`IsoInstant::resolve`]. Letters may be either case.
+///
+/// Returns `None` if the string is not in this format or is not a real date
+/// and time.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::{iso::parse_instant, zone::Zone};
+/// use time::macros::{datetime, offset};
+///
+/// let utc = Zone::Fixed(offset!(UTC));
+/// assert_eq!(
+///     parse_instant("2026-10-16T17:00:00+02:00").map(|instant| instant.resolve(utc)),
+///     Some(datetime!(2026-10-16 17:00 +2))
+/// );
+/// assert_eq!(
+///     parse_instant("2026-10-16t15:00z").map(|instant| instant.resolve(utc)),
+///     Some(datetime!(2026-10-16 15:00 UTC))
+/// );
+///
+/// let local = parse_instant("2026-10-16T15:00").unwrap();
+/// assert_eq!(local.offset, None);
+/// assert_eq!(
+///     local.resolve(Zone::Fixed(offset!(-5))),
+///     datetime!(2026-10-16 15:00 -5)
+/// );
+///
+/// assert_eq!(parse_instant("2026-10-16T25:00Z"), None);
+/// ```
+pub fn parse_instant(s: &str) -> Option<IsoInstant> {
+    let date_len = date::iso_date_len(s)?;
+    let date = date::parse_iso_date(&s[..date_len])?;
+    let rest = s[date_len..].strip_prefix(['t', 'T'])?;
+
+    let offset_start = rest.find(['z', 'Z', '+', '-']).unwrap_or(rest.len());
+    let (time, offset) = rest.split_at(offset_start);
+    let time = parse_time(time)?;
+    let offset = match offset {
+        "" => None,
+        "z" | "Z" => Some(UtcOffset::UTC),
+        offset => Some(parse_offset(offset)?),
+    };
+
+    Some(IsoInstant {
+        datetime: PrimitiveDateTime::new(date, time),
+        offset,
+    })
+}
+
+/// Finds the length of the ISO 8601 date and time at the start of `s`, if
+/// there is one.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::instant_len;
+///
+/// assert_eq!(instant_len("2026-10-16T17:00+02:00 - 5m"), Some(22));
+/// assert_eq!(instant_len("2026-10-16 17:00"), None);
+/// ```
+pub fn instant_len(s: &str) -> Option<usize> {
+    let date_len = date::iso_date_len(s)?;
+    let len = date_len
+        + s[date_len..]
+            .bytes()
+            .enumerate()
+            .take_while(|&(i, b)| {
+                (i == 0 && b.eq_ignore_ascii_case(&b't'))
+                    || (i > 0 && (b.is_ascii_digit() || b":.,+-zZ".contains(&b)))
+            })
+            .count();
+    parse_instant(&s[..len]).map(|_| len)
+}
+
+/// Parses a time like "17:00", "17:00:30" or "17:00:30.5".
+fn parse_time(s: &str) -> Option<Time> {
+    let mut parts = s.split(':');
+    let hour = parse_two_digits(parts.next()?)?;
+    let min = parse_two_digits(parts.next()?)?;
+    let (sec, nanos) = match parts.next() {
+        Some(sec) => {
+            let (whole, fraction) = sec.split_once(['.', ',']).unwrap_or((sec, ""));
+            let nanos = if fraction.is_empty() {
+                0
+            } else {
+                if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                // only the first 9 digits fit in nanoseconds
+                let digits = &fraction[..fraction.len().min(9)];
+                digits.parse::<u32>().ok()? * 10_u32.pow(9 - digits.len() as u32)
+            };
+            (parse_two_digits(whole)?, nanos)
+        }
+        None => (0, 0),
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Time::from_hms_nano(hour, min, sec, nanos).ok()
+}
+
+/// Parses an offset like "+02:00", "-0530" or "+02".
+fn parse_offset(s: &str) -> Option<UtcOffset> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, mins) = match rest.len() {
+        2 => (rest, "00"),
+        4 => rest.split_at(2),
+        5 if rest.as_bytes()[2] == b':' => (&rest[..2], &rest[3..]),
+        _ => return None,
+    };
+
+    let hours = i8::try_from(parse_two_digits(hours)?).ok()?;
+    let mins = i8::try_from(parse_two_digits(mins)?).ok()?;
+    UtcOffset::from_hms(sign * hours, sign * mins, 0).ok()
+}
+
+/// Parses exactly two digits.
+fn parse_two_digits(s: &str) -> Option<u8> {
+    (s.len() == 2 && s.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| s.parse().ok())
+        .flatten()
+}
+
+/// Formats a duration in ISO 8601, like "PT1H30M" or "P2DT3H".
+///
+/// Only days, hours, minutes and seconds are used, as the length of longer
+/// units depends on the calendar. Anything smaller than a millisecond is
+/// removed. Negative durations start with a "-".
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::format_duration;
+/// use time::{ext::NumericalDuration, Duration};
+///
+/// assert_eq!(format_duration(90.minutes()), "PT1H30M");
+/// assert_eq!(format_duration(2.days() + 3.hours()), "P2DT3H");
+/// assert_eq!(format_duration(1500.milliseconds()), "PT1.5S");
+/// assert_eq!(format_duration(Duration::ZERO), "PT0S");
+/// assert_eq!(format_duration((-5).minutes()), "-PT5M");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let sign = if duration.is_negative() { "-" } else { "" };
+    let duration = duration.abs();
+
+    let days = duration.whole_days();
+    let hours = duration.whole_hours() % 24;
+    let mins = duration.whole_minutes() % 60;
+    let secs = duration.whole_seconds() % 60;
+    let millis = duration.subsec_milliseconds();
+
+    let mut output = format!("{sign}P");
+    let write_err = "writing to a string cannot fail";
+    if days != 0 {
+        write!(output, "{days}D").expect(write_err);
+    }
+    if hours != 0 || mins != 0 || secs != 0 || millis != 0 || days == 0 {
+        output.push('T');
+        if hours != 0 {
+            write!(output, "{hours}H").expect(write_err);
+        }
+        if mins != 0 {
+            write!(output, "{mins}M").expect(write_err);
+        }
+        if millis != 0 {
+            let millis = format!("{millis:03}");
+            write!(output, "{secs}.{}S", millis.trim_end_matches('0')).expect(write_err);
+        } else if secs != 0 || (hours == 0 && mins == 0) {
+            write!(output, "{secs}S").expect(write_err);
+        }
+    }
+    output
+}
+
+/// Formats a date and time in ISO 8601, like "2026-10-16T17:00:00+02:00".
+///
+/// Anything smaller than a second is removed, and UTC is written as "Z".
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::format_instant;
+/// use time::macros::datetime;
+///
+/// assert_eq!(
+///     format_instant(datetime!(2026-10-16 17:00:30.5 +2)),
+///     "2026-10-16T17:00:30+02:00"
+/// );
+/// assert_eq!(
+///     format_instant(datetime!(2026-10-16 15:00 UTC)),
+///     "2026-10-16T15:00:00Z"
+/// );
+/// ```
+pub fn format_instant(datetime: OffsetDateTime) -> String {
+    let offset = datetime.offset();
+    let offset = if offset.is_utc() {
+        "Z".to_string()
+    } else {
+        let sign = if offset.is_negative() { '-' } else { '+' };
+        format!(
+            "{sign}{:02}:{:02}",
+            offset.whole_hours().unsigned_abs(),
+            offset.minutes_past_hour().unsigned_abs()
+        )
+    };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{offset}",
+        datetime.year(),
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second(),
+    )
+}
+
+/// Formats the time interval that starts at `start` and lasts for `duration`
+/// in ISO 8601, like "2026-10-16T17:00:00+02:00/PT25M".
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::iso::format_interval;
+/// use time::{ext::NumericalDuration, macros::datetime};
+///
+/// assert_eq!(
+///     format_interval(datetime!(2026-10-16 17:00 +2), 25.minutes()),
+///     "2026-10-16T17:00:00+02:00/PT25M"
+/// );
+/// assert_eq!(
+///     format_interval(datetime!(2026-10-16 23:30 UTC), 90.minutes()),
+///     "2026-10-16T23:30:00Z/PT1H30M"
+/// );
+/// ```
+pub fn format_interval(start: OffsetDateTime, duration: Duration) -> String {
+    format!("{}/{}", format_instant(start), format_duration(duration))
+}
@@ -0,0 +1,283 @@
+use std::{fmt, str::FromStr};
+
+use crate::interpreter;
+
+use super::{meridiem::Meridiem, units::TimeUnit};
+
+/// The words and number format of a language, which inputs can be written in.
+///
+/// Only units, meridiems and number words are translated. Days like
+/// "tomorrow", phrases like "half past 3" and keywords like "at" and "until"
+/// are always in English.
+///
+/// All words are lowercase.
+#[derive(Debug, PartialEq)]
+pub struct Vocabulary {
+    /// The character between the whole and decimal part of a number. A "." is
+    /// always accepted too.
+    pub decimal_separator: char,
+    /// The words for each unit.
+    pub units: [(TimeUnit, &'static [&'static str]); 8],
+    /// The words for am, like "am".
+    pub am: &'static [&'static str],
+    /// The words for pm, like "pm".
+    pub pm: &'static [&'static str],
+    /// Words that are whole numbers.
+    pub numbers: &'static [(&'static str, f64)],
+    /// Words that are 1 when they come before another word, like "an hour".
+    pub articles: &'static [&'static str],
+    /// Words that are a fraction of whatever they are next to.
+    pub fractions: &'static [(&'static str, f64)],
+    /// Words that join amounts, like "two and a half hours".
+    pub and: &'static [&'static str],
+}
+
+impl Vocabulary {
+    /// The unit that `word` is, if any.
+    pub fn unit(&self, word: &str) -> Option<TimeUnit> {
+        self.units
+            .iter()
+            .find(|(_, tokens)| tokens.contains(&word))
+            .map(|(unit, _)| *unit)
+    }
+
+    /// The meridiem that `word` is, if any.
+    pub fn meridiem(&self, word: &str) -> Option<Meridiem> {
+        if self.am.contains(&word) {
+            Some(Meridiem::Ante)
+        } else if self.pm.contains(&word) {
+            Some(Meridiem::Post)
+        } else {
+            None
+        }
+    }
+
+    /// Every unit and meridiem word, to suggest from when a word is unknown.
+    pub fn words(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.units
+            .iter()
+            .flat_map(|(_, tokens)| tokens.iter().copied())
+            .chain(self.am.iter().copied())
+            .chain(self.pm.iter().copied())
+    }
+}
+
+/// English, the default, like "1.5 hours" or "an hour and a half".
+pub const ENGLISH: Vocabulary = Vocabulary {
+    decimal_separator: '.',
+    units: [
+        (TimeUnit::Milli, &TimeUnit::MILLI_TOKENS),
+        (TimeUnit::Sec, &TimeUnit::SEC_TOKENS),
+        (TimeUnit::Min, &TimeUnit::MIN_TOKENS),
+        (TimeUnit::Hour, &TimeUnit::HOUR_TOKENS),
+        (TimeUnit::Day, &TimeUnit::DAY_TOKENS),
+        (TimeUnit::Week, &TimeUnit::WEEK_TOKENS),
+        (TimeUnit::Month, &TimeUnit::MONTH_TOKENS),
+        (TimeUnit::Year, &TimeUnit::YEAR_TOKENS),
+    ],
+    am: &Meridiem::AM_TOKENS,
+    pm: &Meridiem::PM_TOKENS,
+    numbers: &[
+        ("one", 1.0),
+        ("two", 2.0),
+        ("three", 3.0),
+        ("four", 4.0),
+        ("five", 5.0),
+        ("six", 6.0),
+        ("seven", 7.0),
+        ("eight", 8.0),
+        ("nine", 9.0),
+        ("ten", 10.0),
+        ("eleven", 11.0),
+        ("twelve", 12.0),
+        ("thirteen", 13.0),
+        ("fourteen", 14.0),
+        ("fifteen", 15.0),
+        ("sixteen", 16.0),
+        ("seventeen", 17.0),
+        ("eighteen", 18.0),
+        ("nineteen", 19.0),
+        ("twenty", 20.0),
+        ("thirty", 30.0),
+        ("forty", 40.0),
+        ("fifty", 50.0),
+        ("sixty", 60.0),
+        ("seventy", 70.0),
+        ("eighty", 80.0),
+        ("ninety", 90.0),
+        ("hundred", 100.0),
+    ],
+    articles: &["a", "an"],
+    fractions: &[
+        ("half", 0.5),
+        ("halves", 0.5),
+        ("quarter", 0.25),
+        ("quarters", 0.25),
+    ],
+    and: &["and"],
+};
+
+/// German, like "1,5 Std" or "eine halbe Stunde".
+pub const GERMAN: Vocabulary = Vocabulary {
+    decimal_separator: ',',
+    units: [
+        (TimeUnit::Milli, &["ms", "millisekunde", "millisekunden"]),
+        (TimeUnit::Sec, &["s", "sek", "sekunde", "sekunden"]),
+        (TimeUnit::Min, &["m", "min", "minute", "minuten"]),
+        (TimeUnit::Hour, &["h", "std", "stunde", "stunden"]),
+        (TimeUnit::Day, &["d", "t", "tag", "tage", "tagen"]),
+        (TimeUnit::Week, &["w", "wo", "woche", "wochen"]),
+        (
+            TimeUnit::Month,
+            &["mo", "mon", "monat", "monate", "monaten"],
+        ),
+        (TimeUnit::Year, &["j", "jahr", "jahre", "jahren"]),
+    ],
+    am: &["am", "vorm"],
+    pm: &["pm", "nachm"],
+    numbers: &[
+        ("eins", 1.0),
+        ("zwei", 2.0),
+        ("drei", 3.0),
+        ("vier", 4.0),
+        ("fünf", 5.0),
+        ("sechs", 6.0),
+        ("sieben", 7.0),
+        ("acht", 8.0),
+        ("neun", 9.0),
+        ("zehn", 10.0),
+        ("elf", 11.0),
+        ("zwölf", 12.0),
+        ("dreizehn", 13.0),
+        ("vierzehn", 14.0),
+        ("fünfzehn", 15.0),
+        ("sechzehn", 16.0),
+        ("siebzehn", 17.0),
+        ("achtzehn", 18.0),
+        ("neunzehn", 19.0),
+        ("zwanzig", 20.0),
+        ("dreißig", 30.0),
+        ("vierzig", 40.0),
+        ("fünfzig", 50.0),
+        ("sechzig", 60.0),
+        ("siebzig", 70.0),
+        ("achtzig", 80.0),
+        ("neunzig", 90.0),
+        ("hundert", 100.0),
+    ],
+    articles: &["ein", "eine", "einen", "einer"],
+    // "halb" is left out, as "halb 3" is a time (2:30)
+    fractions: &[("halbe", 0.5), ("halben", 0.5), ("viertel", 0.25)],
+    and: &["und"],
+};
+
+/// French, like "1,5 h" or "une heure et demie".
+pub const FRENCH: Vocabulary = Vocabulary {
+    decimal_separator: ',',
+    units: [
+        (TimeUnit::Milli, &["ms", "milliseconde", "millisecondes"]),
+        (TimeUnit::Sec, &["s", "sec", "seconde", "secondes"]),
+        (TimeUnit::Min, &["m", "min", "minute", "minutes"]),
+        (TimeUnit::Hour, &["h", "heure", "heures"]),
+        (TimeUnit::Day, &["j", "jour", "jours"]),
+        (TimeUnit::Week, &["sem", "semaine", "semaines"]),
+        (TimeUnit::Month, &["mois"]),
+        (
+            TimeUnit::Year,
+            &["an", "ans", "année", "années", "annee", "annees"],
+        ),
+    ],
+    am: &["am"],
+    pm: &["pm"],
+    numbers: &[
+        ("deux", 2.0),
+        ("trois", 3.0),
+        ("quatre", 4.0),
+        ("cinq", 5.0),
+        ("six", 6.0),
+        ("sept", 7.0),
+        ("huit", 8.0),
+        ("neuf", 9.0),
+        ("dix", 10.0),
+        ("onze", 11.0),
+        ("douze", 12.0),
+        ("treize", 13.0),
+        ("quatorze", 14.0),
+        ("quinze", 15.0),
+        ("seize", 16.0),
+        ("vingt", 20.0),
+        ("trente", 30.0),
+        ("quarante", 40.0),
+        ("cinquante", 50.0),
+        ("soixante", 60.0),
+        ("cent", 100.0),
+    ],
+    // "un" is only an article, so "un" by itself is not a number
+    articles: &["un", "une"],
+    fractions: &[
+        ("demi", 0.5),
+        ("demie", 0.5),
+        ("quart", 0.25),
+        ("quarts", 0.25),
+    ],
+    and: &["et"],
+};
+
+/// A language that inputs can be written in.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+}
+
+impl Locale {
+    /// Every locale, in the order they are shown in settings.
+    pub const ALL: [Self; 3] = [Self::English, Self::German, Self::French];
+
+    /// The words and number format used in this locale.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use minti_ui::time::{locale::Locale, units::TimeUnit};
+    ///
+    /// let vocabulary = Locale::German.vocabulary();
+    /// assert_eq!(vocabulary.unit("std"), Some(TimeUnit::Hour));
+    /// assert_eq!(vocabulary.unit("hrs"), None);
+    /// assert_eq!(vocabulary.decimal_separator, ',');
+    /// ```
+    pub const fn vocabulary(self) -> &'static Vocabulary {
+        match self {
+            Self::English => &ENGLISH,
+            Self::German => &GERMAN,
+            Self::French => &FRENCH,
+        }
+    }
+
+    /// The language code used to store this locale as a setting.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+            Self::French => "fr",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = interpreter::ErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.name() == s)
+            .ok_or_else(|| Self::Err::Other(format!("Unknown locale \"{s}\"")))
+    }
+}
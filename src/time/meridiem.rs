@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use time::Time;
 
@@ -14,20 +14,109 @@ pub enum Meridiem {
 impl Meridiem {
     pub const AM_TOKENS: [&'static str; 2] = ["am", "a.m."];
     pub const PM_TOKENS: [&'static str; 2] = ["pm", "p.m."];
+
+    /// Every token that is a meridiem.
+    pub fn tokens() -> impl Iterator<Item = &'static str> {
+        Self::AM_TOKENS.into_iter().chain(Self::PM_TOKENS)
+    }
 }
 
 impl FromStr for Meridiem {
-    type Err = interpreter::Error;
+    type Err = interpreter::ErrorKind;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             s if Self::AM_TOKENS.contains(&s) => Self::Ante,
             s if Self::PM_TOKENS.contains(&s) => Self::Post,
-            s => Err(Self::Err::InvalidUnit(s.to_string()))?,
+            s => Err(Self::Err::invalid_unit(s, Self::tokens()))?,
         })
     }
 }
 
+/// How a time with no am or pm, like "3:30", is read when its hour could be
+/// either.
+///
+/// Hours of 0 or 13-23 are always 24-hour time, regardless of the policy.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum MeridiemPolicy {
+    /// Whichever of am or pm comes first. Times on a specific day use 24-hour
+    /// time instead.
+    #[default]
+    Closest,
+    /// Whichever of am or pm comes first, including on a specific day.
+    NextFuture,
+    /// Whichever is during working hours, so 7-11 are am and 12 and 1-6 are
+    /// pm.
+    WorkingHours,
+    /// Always 24-hour time, so "3:30" is 3:30am.
+    Strict24h,
+}
+
+impl MeridiemPolicy {
+    /// Every policy, in the order they are shown in settings.
+    pub const ALL: [Self; 4] = [
+        Self::Closest,
+        Self::NextFuture,
+        Self::WorkingHours,
+        Self::Strict24h,
+    ];
+
+    /// The meridiems that `hour` could be in, whichever comes first being
+    /// used. Empty if it should be read as 24-hour time.
+    ///
+    /// `on_day` is whether the time is on a specific day, like "fri 3:30".
+    ///
+    /// # Examples
+    /// ```rust
+    /// use minti_ui::time::meridiem::{
+    ///     Meridiem::{Ante, Post},
+    ///     MeridiemPolicy,
+    /// };
+    ///
+    /// assert_eq!(MeridiemPolicy::Closest.meridiems(3, false), [Ante, Post]);
+    /// assert_eq!(MeridiemPolicy::Closest.meridiems(3, true), []);
+    /// assert_eq!(MeridiemPolicy::WorkingHours.meridiems(9, false), [Ante]);
+    /// assert_eq!(MeridiemPolicy::WorkingHours.meridiems(3, true), [Post]);
+    /// assert_eq!(MeridiemPolicy::Strict24h.meridiems(3, false), []);
+    /// ```
+    pub const fn meridiems(self, hour: u8, on_day: bool) -> &'static [Meridiem] {
+        match self {
+            Self::Closest if on_day => &[],
+            Self::Closest | Self::NextFuture => &[Meridiem::Ante, Meridiem::Post],
+            Self::WorkingHours if matches!(hour, 7..=11) => &[Meridiem::Ante],
+            Self::WorkingHours => &[Meridiem::Post],
+            Self::Strict24h => &[],
+        }
+    }
+
+    /// The name used to store this policy as a setting.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Closest => "closest",
+            Self::NextFuture => "next",
+            Self::WorkingHours => "working-hours",
+            Self::Strict24h => "24h",
+        }
+    }
+}
+
+impl fmt::Display for MeridiemPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for MeridiemPolicy {
+    type Err = interpreter::ErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.name() == s)
+            .ok_or_else(|| Self::Err::Other(format!("Unknown time policy \"{s}\"")))
+    }
+}
+
 /// Makes a new `Time` from 12 hour notation.
 /// - 12am becomes 00:00:00.
 /// - 12pm becomes 12:00:00.
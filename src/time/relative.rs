@@ -1,26 +1,81 @@
-use std::ops::Add;
+use time::{ext::NumericalDuration, Date, Duration, Month, OffsetDateTime, Time};
 
-use time::{ext::NumericalDuration, Duration, OffsetDateTime, Time};
+use super::{date::TargetDate, zone::Zone};
 
 /// Calculates the next `OffsetDateTime` with the specified `target_time`
 /// that is closest to the current datetime.
 ///
 /// The `OffsetDateTime` will be on the same day if it is not `target_time` yet,
-/// otherwise it will be on the next day.
+/// otherwise it will be on the next day. The local time zone is used, so the
+/// offset may be different to the current one if daylight saving time starts
+/// or ends before then.
 ///
 /// If it is currently the exact `target_time`, the next day will be returned.
 ///
 /// # Panics
 /// Panics if the local time cannot be determined.
 pub fn get_next_occurrence(target_time: Time) -> OffsetDateTime {
-    let current_time = now().time();
+    get_next_occurrence_in(target_time, Zone::local())
+}
+
+/// Calculates the next `OffsetDateTime` where the wall clock in `zone` shows
+/// `target_time`.
+///
+/// Like [`get_next_occurrence`], but the day is the current day in `zone`.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn get_next_occurrence_in(target_time: Time, zone: Zone) -> OffsetDateTime {
+    next_occurrence_from(now(), target_time, zone)
+}
+
+/// Calculates the first `OffsetDateTime` after `from` where the wall clock in
+/// `zone` shows `target_time`.
+///
+/// The offset of `zone` is looked up for the day that is found, so this is
+/// correct across daylight saving time changes. See [`Zone::resolve`] for
+/// times that are skipped or repeated by a change.
+///
+/// # Example
+/// ```rust
+/// use minti_ui::time::{relative::next_occurrence_from, zone::parse_zone};
+/// use time::macros::{datetime, time};
+///
+/// let berlin = parse_zone("Europe/Berlin").unwrap();
+///
+/// // the clocks go forward overnight, so it is only 8 hours away
+/// let spring = datetime!(2026-03-28 22:00 +1);
+/// let alarm = next_occurrence_from(spring, time!(7:00), berlin);
+/// assert_eq!(alarm, datetime!(2026-03-29 7:00 +2));
+/// assert_eq!((alarm - spring).whole_hours(), 8);
+///
+/// // and back, so it is 10 hours away
+/// let autumn = datetime!(2026-10-24 22:00 +2);
+/// let alarm = next_occurrence_from(autumn, time!(7:00), berlin);
+/// assert_eq!(alarm, datetime!(2026-10-25 7:00 +1));
+/// assert_eq!((alarm - autumn).whole_hours(), 10);
+///
+/// // 2:30 is skipped over in spring, and happens twice in autumn
+/// assert_eq!(
+///     next_occurrence_from(spring, time!(2:30), berlin),
+///     datetime!(2026-03-29 3:30 +2)
+/// );
+/// assert_eq!(
+///     next_occurrence_from(autumn, time!(2:30), berlin),
+///     datetime!(2026-10-25 2:30 +2)
+/// );
+/// ```
+pub fn next_occurrence_from(from: OffsetDateTime, target_time: Time, zone: Zone) -> OffsetDateTime {
+    let today = zone.convert(from).date();
 
-    if current_time < target_time {
+    let occurrence = zone.resolve(today.with_time(target_time));
+    if from < occurrence {
         // same day
-        now().replace_time(target_time)
+        occurrence
     } else {
         // next day
-        now().add(1.days()).replace_time(target_time)
+        let tomorrow = today.next_day().expect("should not be the last day");
+        zone.resolve(tomorrow.with_time(target_time))
     }
 }
 
@@ -32,7 +87,205 @@ pub fn get_next_occurrence(target_time: Time) -> OffsetDateTime {
 /// # Panics
 /// Panics if the local time cannot be determined.
 pub fn duration_until_time(target_time: Time) -> Duration {
-    get_next_occurrence(target_time) - now()
+    duration_until_time_in(target_time, Zone::local())
+}
+
+/// Calculates the duration until the wall clock in `zone` next shows
+/// `target_time`.
+///
+/// See [`get_next_occurrence_in`] for how the day is resolved.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn duration_until_time_in(target_time: Time, zone: Zone) -> Duration {
+    let now = now();
+    next_occurrence_from(now, target_time, zone) - now
+}
+
+/// Calculates the `OffsetDateTime` at `target_time` on the specified day.
+///
+/// A weekday resolves to today if it is that weekday and `target_time` has
+/// not passed yet, otherwise the next day that is that weekday. The result
+/// may be in the past for the other variants.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn get_occurrence_on(target_date: TargetDate, target_time: Time) -> OffsetDateTime {
+    get_occurrence_on_in(target_date, target_time, Zone::local())
+}
+
+/// Calculates the `OffsetDateTime` where the wall clock in `zone` shows
+/// `target_time` on the specified day.
+///
+/// Like [`get_occurrence_on`], but days are resolved from the current day in
+/// `zone`.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn get_occurrence_on_in(
+    target_date: TargetDate,
+    target_time: Time,
+    zone: Zone,
+) -> OffsetDateTime {
+    occurrence_on_from(now(), target_date, target_time, zone)
+}
+
+/// Calculates the `OffsetDateTime` where the wall clock in `zone` shows
+/// `target_time` on the specified day, with days resolved from `from`.
+///
+/// Like [`next_occurrence_from`], the offset of `zone` is looked up for the
+/// day that is found.
+///
+/// # Example
+/// ```rust
+/// use minti_ui::time::{date::TargetDate, relative::occurrence_on_from, zone::parse_zone};
+/// use time::macros::{datetime, time};
+///
+/// let new_york = parse_zone("America/New_York").unwrap();
+/// // the clocks go forward on the Sunday
+/// let friday = datetime!(2026-03-06 18:00 -5);
+///
+/// assert_eq!(
+///     occurrence_on_from(friday, TargetDate::Tomorrow, time!(9:00), new_york),
+///     datetime!(2026-03-07 9:00 -5)
+/// );
+/// assert_eq!(
+///     occurrence_on_from(friday, TargetDate::Weekday(time::Weekday::Monday), time!(9:00), new_york),
+///     datetime!(2026-03-09 9:00 -4)
+/// );
+/// ```
+pub fn occurrence_on_from(
+    from: OffsetDateTime,
+    target_date: TargetDate,
+    target_time: Time,
+    zone: Zone,
+) -> OffsetDateTime {
+    let today = zone.convert(from).date();
+    let date = match target_date {
+        TargetDate::Today => today,
+        TargetDate::Tomorrow => today.next_day().expect("should not be the last day"),
+        TargetDate::Weekday(weekday) => {
+            let days_ahead = (7 + weekday.number_days_from_monday()
+                - today.weekday().number_days_from_monday())
+                % 7;
+            let date = today + i64::from(days_ahead).days();
+            if days_ahead == 0 && from >= zone.resolve(date.with_time(target_time)) {
+                date + 7.days()
+            } else {
+                date
+            }
+        }
+        TargetDate::Date(date) => date,
+    };
+
+    zone.resolve(date.with_time(target_time))
+}
+
+/// Calculates the duration until `target_time` on the specified day.
+///
+/// See [`get_occurrence_on`] for how the day is resolved. The duration will
+/// be negative if the time is in the past.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn duration_until_time_on(target_date: TargetDate, target_time: Time) -> Duration {
+    duration_until_time_on_in(target_date, target_time, Zone::local())
+}
+
+/// Calculates the duration until the wall clock in `zone` shows
+/// `target_time` on the specified day.
+///
+/// See [`get_occurrence_on_in`] for how the day is resolved. The duration
+/// will be negative if the time is in the past.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn duration_until_time_on_in(
+    target_date: TargetDate,
+    target_time: Time,
+    zone: Zone,
+) -> Duration {
+    let now = now();
+    occurrence_on_from(now, target_date, target_time, zone) - now
+}
+
+/// Adds a number of calendar months to a datetime, keeping the same time.
+///
+/// The day is clamped to the end of the resulting month, so adding 1 month to
+/// January 31 gives the last day of February.
+///
+/// Returns `None` if the resulting year is out of range.
+///
+/// # Example
+/// ```rust
+/// use minti_ui::time::relative::add_months;
+/// use time::macros::datetime;
+///
+/// assert_eq!(
+///     add_months(datetime!(2026-01-31 9:00 UTC), 1),
+///     Some(datetime!(2026-02-28 9:00 UTC))
+/// );
+/// assert_eq!(
+///     add_months(datetime!(2026-10-16 9:00 UTC), 14),
+///     Some(datetime!(2027-12-16 9:00 UTC))
+/// );
+/// assert_eq!(add_months(datetime!(2026-10-16 9:00 UTC), 10_000 * 12), None);
+/// assert_eq!(add_months(datetime!(2026-10-16 9:00 UTC), i64::MAX), None);
+/// ```
+pub fn add_months(datetime: OffsetDateTime, months: i64) -> Option<OffsetDateTime> {
+    let date = datetime.date();
+    let total_months = (i64::from(date.year()) * 12 + i64::from(u8::from(date.month()) - 1))
+        .checked_add(months)?;
+    let year = i32::try_from(total_months.div_euclid(12)).ok()?;
+    let month = Month::try_from(total_months.rem_euclid(12) as u8 + 1)
+        .expect("remainder should be a valid month");
+
+    // no day is valid if the year is out of range
+    let date = (1..=date.day())
+        .rev()
+        .find_map(|day| Date::from_calendar_date(year, month, day).ok())?;
+    Some(datetime.replace_date(date))
+}
+
+/// Calculates the duration until the same time, a number of calendar months
+/// from now.
+///
+/// See [`add_months`] for how days past the end of the month are handled. The
+/// time is the same on the wall clock, even if the local offset is different
+/// by then.
+///
+/// Returns `None` if the resulting year is out of range.
+///
+/// # Panics
+/// Panics if the local time cannot be determined.
+pub fn duration_of_months(months: i64) -> Option<Duration> {
+    duration_of_months_from(now(), months, Zone::local())
+}
+
+/// Calculates the duration from `from` until the same wall-clock time in
+/// `zone`, a number of calendar months later.
+///
+/// Returns `None` if the resulting year is out of range.
+///
+/// # Example
+/// ```rust
+/// use minti_ui::time::{relative::duration_of_months_from, zone::parse_zone};
+/// use time::{ext::NumericalDuration, macros::datetime};
+///
+/// let berlin = parse_zone("Europe/Berlin").unwrap();
+/// // an hour shorter as the clocks go forward in March
+/// assert_eq!(
+///     duration_of_months_from(datetime!(2026-03-16 9:00 +1), 1, berlin),
+///     Some(31.days() - 1.hours())
+/// );
+/// assert_eq!(
+///     duration_of_months_from(datetime!(2026-03-16 9:00 +1), 10_000 * 12, berlin),
+///     None
+/// );
+/// ```
+pub fn duration_of_months_from(from: OffsetDateTime, months: i64, zone: Zone) -> Option<Duration> {
+    let later = add_months(zone.convert(from), months)?;
+    Some(zone.resolve(later.date().with_time(later.time())) - from)
 }
 
 /// Shortcut for `OffsetDateTime::now_local().unwrap()`
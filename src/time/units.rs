@@ -7,6 +7,7 @@ pub const MILLIS_IN_SEC: u64 = 1000;
 pub const SECS_IN_MIN: u64 = 60;
 pub const MINS_IN_HOUR: u64 = 60;
 pub const HOURS_IN_DAY: u64 = 24;
+pub const DAYS_IN_WEEK: u64 = 7;
 pub const SECS_IN_HOUR: u64 = SECS_IN_MIN * MINS_IN_HOUR;
 pub const SECS_IN_DAY: u64 = SECS_IN_HOUR * HOURS_IN_DAY;
 
@@ -21,6 +22,9 @@ pub enum TimeUnit {
     Min,
     Hour,
     Day,
+    Week,
+    Month,
+    Year,
 }
 
 impl TimeUnit {
@@ -37,11 +41,32 @@ impl TimeUnit {
     pub const MIN_TOKENS: [&'static str; 5] = ["m", "min", "mins", "minute", "minutes"];
     pub const HOUR_TOKENS: [&'static str; 5] = ["h", "hr", "hrs", "hour", "hours"];
     pub const DAY_TOKENS: [&'static str; 3] = ["d", "day", "days"];
+    pub const WEEK_TOKENS: [&'static str; 5] = ["w", "wk", "wks", "week", "weeks"];
+    pub const MONTH_TOKENS: [&'static str; 6] = ["mo", "mos", "mth", "mths", "month", "months"];
+    pub const YEAR_TOKENS: [&'static str; 5] = ["y", "yr", "yrs", "year", "years"];
+
+    /// Every token that is a unit, from smallest to largest unit.
+    pub fn tokens() -> impl Iterator<Item = &'static str> {
+        Self::MILLI_TOKENS
+            .into_iter()
+            .chain(Self::SEC_TOKENS)
+            .chain(Self::MIN_TOKENS)
+            .chain(Self::HOUR_TOKENS)
+            .chain(Self::DAY_TOKENS)
+            .chain(Self::WEEK_TOKENS)
+            .chain(Self::MONTH_TOKENS)
+            .chain(Self::YEAR_TOKENS)
+    }
+
+    /// The average length of a month in the Gregorian calendar, in days.
+    const AVG_DAYS_IN_MONTH: f64 = 30.436_875;
+    /// The average length of a year in the Gregorian calendar, in days.
+    const AVG_DAYS_IN_YEAR: f64 = 365.2425;
 
     /// Converts a unit to a number, which can be used to get other units
     /// relative to `self`.
     ///
-    /// The number starts from milliseconds (0) to days (4).
+    /// The number starts from milliseconds (0) to years (7).
     ///
     /// The number only makes sense when used in `number_to_variant`.
     #[must_use]
@@ -52,6 +77,9 @@ impl TimeUnit {
             Self::Min => 2,
             Self::Hour => 3,
             Self::Day => 4,
+            Self::Week => 5,
+            Self::Month => 6,
+            Self::Year => 7,
         }
     }
 
@@ -60,7 +88,7 @@ impl TimeUnit {
     /// Only makes sense when used with the number returned in `numeric_value`.
     ///
     /// Returns `None` if `num` does not map to one of the variants (must be
-    /// in range `0..=4`).
+    /// in range `0..=7`).
     #[must_use]
     const fn number_to_variant(num: u8) -> Option<Self> {
         if num == 0 {
@@ -73,6 +101,12 @@ impl TimeUnit {
             Some(Self::Hour)
         } else if num == 4 {
             Some(Self::Day)
+        } else if num == 5 {
+            Some(Self::Week)
+        } else if num == 6 {
+            Some(Self::Month)
+        } else if num == 7 {
+            Some(Self::Year)
         } else {
             None
         }
@@ -80,14 +114,14 @@ impl TimeUnit {
 
     /// Gets the unit that is one unit larger than `self`.
     ///
-    /// Returns `None` if the unit is already the largest (Day).
+    /// Returns `None` if the unit is already the largest (Year).
     ///
     /// # Example
     /// ```rust
     /// # use minti_ui::time::units::TimeUnit;
     ///
     /// assert_eq!(TimeUnit::Sec.larger_unit(), Some(TimeUnit::Min));
-    /// assert_eq!(TimeUnit::Day.larger_unit(), None);
+    /// assert_eq!(TimeUnit::Year.larger_unit(), None);
     /// ```
     pub const fn larger_unit(&self) -> Option<Self> {
         Self::number_to_variant(self.numeric_value().saturating_add(1))
@@ -110,6 +144,12 @@ impl TimeUnit {
 
     /// Converts a number to a duration using the unit of `self`.
     ///
+    /// Months and years do not have a fixed length, so this uses their
+    /// average length. Use [`calendar_months`] to check if the calendar
+    /// should be used instead.
+    ///
+    /// [`calendar_months`]: TimeUnit::calendar_months
+    ///
     /// # Example
     /// ```rust
     /// # use minti_ui::time::units::TimeUnit;
@@ -126,12 +166,58 @@ impl TimeUnit {
             Self::Min => value.minutes(),
             Self::Hour => value.hours(),
             Self::Day => value.days(),
+            Self::Week => value.weeks(),
+            Self::Month => (value * Self::AVG_DAYS_IN_MONTH).days(),
+            Self::Year => (value * Self::AVG_DAYS_IN_YEAR).days(),
+        }
+    }
+
+    /// Converts a number to a duration like [`Self::to_duration`], but returns
+    /// `None` if the duration is too long instead of panicking.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use minti_ui::time::units::TimeUnit;
+    /// use time::ext::NumericalDuration;
+    ///
+    /// assert_eq!(TimeUnit::Hour.checked_to_duration(3.5), Some(3.5.hours()));
+    /// assert_eq!(TimeUnit::Hour.checked_to_duration(1e20), None);
+    /// ```
+    pub fn checked_to_duration(&self, value: f64) -> Option<Duration> {
+        let seconds = match self {
+            Self::Milli => value / 1000.0,
+            Self::Sec => value,
+            Self::Min => value * 60.0,
+            Self::Hour => value * 3600.0,
+            Self::Day => value * 86_400.0,
+            Self::Week => value * 604_800.0,
+            Self::Month => value * Self::AVG_DAYS_IN_MONTH * 86_400.0,
+            Self::Year => value * Self::AVG_DAYS_IN_YEAR * 86_400.0,
+        };
+        Duration::checked_seconds_f64(seconds)
+    }
+
+    /// Returns the number of calendar months in one of this unit, if the unit
+    /// depends on the calendar.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use minti_ui::time::units::TimeUnit;
+    ///
+    /// assert_eq!(TimeUnit::Year.calendar_months(), Some(12));
+    /// assert_eq!(TimeUnit::Week.calendar_months(), None);
+    /// ```
+    pub const fn calendar_months(&self) -> Option<i64> {
+        match self {
+            Self::Month => Some(1),
+            Self::Year => Some(12),
+            _ => None,
         }
     }
 }
 
 impl FromStr for TimeUnit {
-    type Err = interpreter::Error;
+    type Err = interpreter::ErrorKind;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
@@ -140,7 +226,10 @@ impl FromStr for TimeUnit {
             s if Self::MIN_TOKENS.contains(&s) => Self::Min,
             s if Self::HOUR_TOKENS.contains(&s) => Self::Hour,
             s if Self::DAY_TOKENS.contains(&s) => Self::Day,
-            s => Err(Self::Err::InvalidUnit(s.to_string()))?,
+            s if Self::WEEK_TOKENS.contains(&s) => Self::Week,
+            s if Self::MONTH_TOKENS.contains(&s) => Self::Month,
+            s if Self::YEAR_TOKENS.contains(&s) => Self::Year,
+            s => Err(Self::Err::invalid_unit(s, Self::tokens()))?,
         })
     }
 }
@@ -0,0 +1,153 @@
+use std::fmt;
+
+use time::{Duration, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time_tz::{system, timezones, Offset, OffsetResult, PrimitiveDateTimeExt, TimeZone, Tz};
+
+use super::relative;
+
+/// A time zone that a specific time can be given in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Zone {
+    /// A fixed offset from UTC, like "UTC+2".
+    Fixed(UtcOffset),
+    /// A zone from the IANA time zone database, like "Europe/Berlin", which
+    /// follows daylight saving time.
+    Named(&'static Tz),
+}
+
+impl Zone {
+    /// The local time zone, from the time zone database if the system's zone
+    /// can be found there. Otherwise, the current local offset.
+    ///
+    /// # Panics
+    /// Panics if the local offset cannot be determined.
+    pub fn local() -> Self {
+        system::get_timezone().map_or_else(|_| Self::Fixed(relative::now().offset()), Self::Named)
+    }
+
+    /// The offset from UTC in this zone at `datetime`.
+    pub fn offset_at(self, datetime: OffsetDateTime) -> UtcOffset {
+        match self {
+            Self::Fixed(offset) => offset,
+            Self::Named(tz) => tz.get_offset_utc(&datetime).to_utc(),
+        }
+    }
+
+    /// Converts `datetime` to the offset it has in this zone.
+    pub fn convert(self, datetime: OffsetDateTime) -> OffsetDateTime {
+        datetime.to_offset(self.offset_at(datetime))
+    }
+
+    /// Finds the instant that the wall clock in this zone shows `datetime`.
+    ///
+    /// If the clocks go back and `datetime` happens twice, the first one is
+    /// used. If the clocks go forward and skip over `datetime`, it is moved
+    /// forward by the length of the gap.
+    pub fn resolve(self, datetime: PrimitiveDateTime) -> OffsetDateTime {
+        match self {
+            Self::Fixed(offset) => datetime.assume_offset(offset),
+            Self::Named(tz) => match datetime.assume_timezone(tz) {
+                OffsetResult::Some(resolved) | OffsetResult::Ambiguous(resolved, _) => resolved,
+                OffsetResult::None => {
+                    // the offset from before the gap pushes it past the gap
+                    let before = datetime.assume_utc() - Duration::DAY;
+                    datetime.assume_offset(tz.get_offset_utc(&before).to_utc())
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(offset) if offset.is_utc() => write!(f, "UTC"),
+            Self::Fixed(offset) => {
+                let sign = if offset.is_negative() { '-' } else { '+' };
+                write!(f, "UTC{sign}{}", offset.whole_hours().unsigned_abs())?;
+                match offset.minutes_past_hour().unsigned_abs() {
+                    0 => Ok(()),
+                    mins => write!(f, ":{mins:02}"),
+                }
+            }
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// Parses a time zone, either an IANA name like "Europe/Berlin" or an offset
+/// from UTC like "UTC+2", "UTC-05:30" or "GMT+0530".
+///
+/// Letters may be either case.
+///
+/// Returns `None` if the string is not a known zone.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::zone::{parse_zone, Zone};
+/// use time::macros::offset;
+///
+/// assert_eq!(parse_zone("utc+2"), Some(Zone::Fixed(offset!(+2))));
+/// assert_eq!(parse_zone("UTC-05:30"), Some(Zone::Fixed(offset!(-5:30))));
+/// assert_eq!(
+///     parse_zone("europe/berlin").map(|zone| zone.to_string()),
+///     Some("Europe/Berlin".to_string())
+/// );
+/// assert_eq!(parse_zone("Mars/Olympus_Mons"), None);
+/// ```
+pub fn parse_zone(s: &str) -> Option<Zone> {
+    let prefix = s.get(..3)?;
+    if (prefix.eq_ignore_ascii_case("utc") || prefix.eq_ignore_ascii_case("gmt")) && s.len() > 3 {
+        return parse_offset(&s[3..]).map(Zone::Fixed);
+    }
+
+    timezones::iter()
+        .find(|tz| tz.name().eq_ignore_ascii_case(s))
+        .map(Zone::Named)
+}
+
+/// Finds the length of the time zone at the start of `s`, if there is one.
+///
+/// # Examples
+/// ```rust
+/// use minti_ui::time::zone::zone_len;
+///
+/// assert_eq!(zone_len("America/New_York tomorrow"), Some(16));
+/// assert_eq!(zone_len("utc+2 + 5m"), Some(5));
+/// assert_eq!(zone_len("5pm"), None);
+/// ```
+pub fn zone_len(s: &str) -> Option<usize> {
+    if !s.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let len = s
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || b"_/+-:".contains(b))
+        .count();
+    parse_zone(&s[..len]).map(|_| len)
+}
+
+/// Parses an offset like "+2", "-05:30" or "+0530".
+fn parse_offset(s: &str) -> Option<UtcOffset> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    if !rest.bytes().all(|b| b.is_ascii_digit() || b == b':') {
+        return None;
+    }
+    let (hours, mins) = match rest.split_once(':') {
+        Some((hours, mins)) if mins.len() == 2 => (hours, mins),
+        Some(_) => return None,
+        None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+        None => (rest, "0"),
+    };
+    if hours.is_empty() || hours.len() > 2 {
+        return None;
+    }
+
+    let hours: i8 = hours.parse().ok()?;
+    let mins: i8 = mins.parse().ok()?;
+    UtcOffset::from_hms(sign * hours, sign * mins, 0).ok()
+}
@@ -1,10 +1,10 @@
+use az::WrappingAs;
 use leptos::*;
-use std::sync::Arc;
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
-use crate::interpreter::{self, interpret_multi, interpret_single, InputIter};
-use crate::time::relative;
+use crate::interpreter::{self, interpret_multi_at, Options, Plan, Presets, Reference, Segment};
+use crate::time::{iso, relative};
 
 pub mod serialize;
 
@@ -60,9 +60,10 @@ impl MultiTimer {
     prop!(status_update: Trigger);
 
     method!(next(&mut self) -> Option<Duration>);
-    method!(peek(&mut self) -> Option<Arc<str>>);
+    method!(peek(&self) -> Option<Segment>);
+    method!(current(&self) -> Option<Segment>);
     method!(reset(&mut self));
-    method!(start(&mut self) -> interpreter::Result<()>);
+    method!(start(&mut self, options: Options, presets: &Presets) -> interpreter::Result<()>);
     method!(pause(&mut self));
     method!(resume(&mut self));
     method!(add_duration(&mut self, duration: Duration));
@@ -90,9 +91,12 @@ struct RawMultiTimer {
     /// Updates when the timer is resumed. This does not include time in the
     /// current pause, if the timer is paused.
     acc_paused_duration: Duration,
-    iter: InputIter,
-    /// Number of timers started, i.e. number of times `next` has been called.
-    consumed: usize,
+    /// When the input was interpreted, which "until" is measured from. Is
+    /// `None` if the timer has not started.
+    planned_at: Option<OffsetDateTime>,
+    iter: Plan,
+    /// The segment that is currently running.
+    current: Option<Segment>,
     pub current_total_duration: ReadSignal<Option<Duration>>,
     set_current_total_duration: WriteSignal<Option<Duration>>,
 
@@ -130,8 +134,9 @@ impl RawMultiTimer {
             start_time,
             last_pause_time,
             acc_paused_duration: Duration::ZERO,
-            iter: InputIter::empty(),
-            consumed: 0,
+            planned_at: None,
+            iter: Plan::empty(),
+            current: None,
             current_total_duration,
             set_current_total_duration,
             started,
@@ -146,20 +151,40 @@ impl RawMultiTimer {
         let next = self.iter.next();
         log::debug!("next = {next:?}");
         if let Some(next) = next {
-            let next_duration = interpret_single(&next)
+            let next_duration = next
+                .duration()
                 .expect("iter should have validated duration inputs already");
             self.restart_current(next_duration);
-            self.consumed += 1;
+            self.current = Some(next);
             self.current_total_duration.get_untracked()
         } else {
             None
         }
     }
 
-    pub fn peek(&mut self) -> Option<Arc<str>> {
+    /// Skips ahead so that the segment at `index` is running, without going
+    /// through the segments before it.
+    ///
+    /// Returns `None` if there is no segment at `index`.
+    pub fn skip_to(&mut self, index: u64) -> Option<Duration> {
+        log::debug!("skipping to segment {index}");
+        self.iter.seek(index);
+        self.next()
+    }
+
+    pub fn peek(&self) -> Option<Segment> {
         self.iter.peek()
     }
 
+    /// Number of timers started, i.e. number of times `next` has been called.
+    pub const fn consumed(&self) -> u64 {
+        self.iter.position()
+    }
+
+    pub fn current(&self) -> Option<Segment> {
+        self.current.clone()
+    }
+
     /// Resets the timer to its initial state, but keeping the title and input.
     pub fn reset(&mut self) {
         log::debug!("resetting timer");
@@ -169,8 +194,9 @@ impl RawMultiTimer {
             (self.set_current_total_duration)(None);
         });
         self.acc_paused_duration = Duration::ZERO;
-        self.iter = InputIter::empty();
-        self.consumed = 0;
+        self.planned_at = None;
+        self.iter = Plan::empty();
+        self.current = None;
     }
 
     pub fn restart_current(&mut self, duration: Duration) {
@@ -188,8 +214,26 @@ impl RawMultiTimer {
         })
     }
 
-    /// The input should be passed in by setting the `input` signal.
-    pub fn start(&mut self) -> interpreter::Result<()> {
+    /// The input should be passed in by setting the `input` signal, and is
+    /// interpreted using `options`, where `presets` can be used by name.
+    ///
+    /// Random durations are picked again each time the timer is started.
+    pub fn start(&mut self, options: Options, presets: &Presets) -> interpreter::Result<()> {
+        // only the low bits change from one start to the next
+        let seed = relative::now().unix_timestamp_nanos().wrapping_as::<u64>();
+        self.start_from(options, presets, seed, Reference::current())
+    }
+
+    /// Like [`Self::start`], but random durations are picked using `seed` and
+    /// the input is interpreted as if at `reference`, so that a stored timer
+    /// gets the same plan when it is restored.
+    pub fn start_from(
+        &mut self,
+        options: Options,
+        presets: &Presets,
+        seed: u64,
+        reference: Reference,
+    ) -> interpreter::Result<()> {
         log::debug!("starting timer with input {}", self.input.get_untracked());
         batch(|| {
             self.reset();
@@ -197,9 +241,12 @@ impl RawMultiTimer {
             // so that the interval loop restarts. restart loop to avoid being
             // highly offset from the actual time (200ms)
             self.running.get_untracked();
-            let iter = self.input.with_untracked(|input| interpret_multi(input))?;
+            let iter = self
+                .input
+                .with_untracked(|input| interpret_multi_at(input, reference, options, presets))?;
 
-            self.iter = iter;
+            self.iter = iter.with_seed(seed);
+            self.planned_at = Some(reference.now);
             self.start_time.set(Some(relative::now()));
             self.next();
             Ok(())
@@ -308,6 +355,18 @@ impl RawMultiTimer {
             .map(|d| d - self.time_elapsed())
     }
 
+    /// The current segment as an ISO 8601 interval, like
+    /// "2026-10-16T17:00:00+02:00/PT25M", for exporting to other tools.
+    ///
+    /// The start is adjusted for any time spent paused, so that the start plus
+    /// the duration is when the segment finishes if it keeps running from
+    /// now. Returns `None` if the timer has not started.
+    pub fn iso_interval(&self) -> Option<String> {
+        let total = self.current_total_duration.get_untracked()?;
+        let start = relative::now() - self.time_elapsed();
+        Some(iso::format_interval(start, total))
+    }
+
     pub fn finished(&self) -> bool {
         self.time_remaining().is_some_and(|t| !t.is_positive())
     }
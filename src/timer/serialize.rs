@@ -4,7 +4,11 @@ use leptos::{SignalGetUntracked, SignalSetUntracked};
 use serde::{Deserialize, Serialize};
 use time::ext::NumericalDuration;
 
-use crate::{contexts::TimerList, time::timestamp};
+use crate::{
+    contexts::TimerList,
+    interpreter::{Options, Presets, Reference},
+    time::{relative, timestamp, zone::Zone},
+};
 
 use super::{MultiTimer, RawMultiTimer};
 
@@ -28,7 +32,20 @@ struct TimerJson {
     /// The title given to the timer.
     title: String,
     /// Number of timers started.
-    consumed: usize,
+    consumed: u64,
+    /// The seed that random durations were picked with.
+    ///
+    /// The duration picked for the current segment is kept in `duration`, so
+    /// it stays the same even if the input would now pick another one, like
+    /// after a preset it uses has changed. Only the segments after it are
+    /// picked again, which gives the same durations as before as long as the
+    /// input has not changed.
+    #[serde(default)]
+    seed: u64,
+    /// The unix timestamp (ms) of when the input was interpreted, which
+    /// "until" is measured from. Defined if the timer has started.
+    #[serde(default)]
+    planned_at: Option<i64>,
 }
 
 impl From<&RawMultiTimer> for TimerJson {
@@ -52,7 +69,9 @@ impl From<&RawMultiTimer> for TimerJson {
                 .saturating_as::<u64>(),
             duration_input: value.input.get_untracked(),
             title: value.title.get_untracked(),
-            consumed: value.consumed,
+            consumed: value.consumed(),
+            seed: value.iter.seed(),
+            planned_at: value.planned_at.map(timestamp::to_unix_millis),
         }
     }
 }
@@ -66,68 +85,240 @@ pub fn stringify_timers(timers: TimerList) -> String {
     serde_json::to_string(&timers).expect("Failed to convert timers to JSON")
 }
 
+/// Describes the current segment of each started timer in ISO 8601, one per
+/// line, for exporting to other tools.
+///
+/// Each line is the interval from [`RawMultiTimer::iso_interval`], followed
+/// by the title if there is one.
+pub fn export_iso(timers: TimerList) -> String {
+    timers
+        .into_iter()
+        .filter_map(|timer| {
+            timer.0.with_value(|t| {
+                let interval = t.iso_interval()?;
+                let title = t.title.get_untracked();
+                Some(format!("{interval} {title}").trim_end().to_string())
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Creates timers from the given JSON string.
 ///
 /// Also sets the timers to the correct state.
 ///
 /// The JSON string should be a list of timers, created by `stringify_timers`.
-/// If any of the timers are invalid, they will be ignored.
+/// Inputs are interpreted using `options`, and can use `presets` by name.
+/// Timers whose input is no longer valid, like after a preset it uses was
+/// removed, or that run fewer segments than were stored as run, are restored
+/// without being started.
 ///
 /// Returns `None` if `json` could not be parsed.
-pub fn parse_timer_json(json: &str) -> Option<TimerList> {
+pub fn parse_timer_json(json: &str, options: Options, presets: &Presets) -> Option<TimerList> {
     let timers: Vec<TimerJson> = serde_json::from_str(json).ok()?;
     let timers: Vec<MultiTimer> = timers
         .into_iter()
-        .filter_map(|unparsed| {
-            let mut timer = RawMultiTimer::new();
-            timer.input.set_untracked(unparsed.duration_input);
-            timer.title.set_untracked(unparsed.title);
-
-            if unparsed.consumed != 0 {
-                timer.start().ok()?;
-                // from 1 because start already advances the iterator
-                (1..unparsed.consumed).for_each(|_| _ = timer.next());
-
-                if unparsed.consumed != timer.consumed {
-                    log::warn!(
-                        "stored {} consumed, only managed to consume {}",
-                        unparsed.consumed,
-                        timer.consumed,
-                    );
-                    return None;
-                }
-            }
+        .map(|unparsed| restore_timer(unparsed, options, presets))
+        .map(|timer| MultiTimer(leptos::StoredValue::new(timer)))
+        .collect();
+
+    Some(TimerList::from_timers(timers))
+}
+
+/// Creates a timer from its JSON representation, in the same state that it
+/// was stored in, or unstarted if it can no longer be.
+fn restore_timer(unparsed: TimerJson, options: Options, presets: &Presets) -> RawMultiTimer {
+    let mut timer = RawMultiTimer::new();
+    timer.input.set_untracked(unparsed.duration_input.clone());
+    timer.title.set_untracked(unparsed.title.clone());
 
-            // timer control methods (start, pause) set their respective properties to now.
-            // must override the times after calling these methods.
-            if let Some(duration) = unparsed.duration {
-                timer
-                    .set_current_total_duration
-                    .set_untracked(Some(duration.saturating_as::<i64>().milliseconds()));
+    if unparsed.consumed != 0 {
+        // the same seed and time give the same plan again, even with random
+        // durations or "until"
+        let reference = Reference {
+            now: unparsed
+                .planned_at
+                .map_or_else(relative::now, timestamp::from_unix_millis),
+            zone: Zone::local(),
+        };
+        let restored = match timer.start_from(options, presets, unparsed.seed, reference) {
+            Ok(()) => restore_progress(&mut timer, &unparsed),
+            Err(e) => {
+                log::warn!("stored input is no longer valid: {e}");
+                None
             }
+        };
+        if restored.is_none() {
+            // keep the input and title, so that it can be fixed or started
+            // again
+            timer.reset();
+        }
+    }
+    timer
+}
 
-            if let Some(start_time) = unparsed.start {
-                timer.start_time.set_untracked(Some(timestamp::from_unix_millis(start_time)));
-            };
+/// Moves a started timer to the segment, times and pause that it was stored
+/// with.
+///
+/// Returns `None` if the input runs fewer segments than were stored as run.
+fn restore_progress(timer: &mut RawMultiTimer, unparsed: &TimerJson) -> Option<()> {
+    // start already runs the first segment
+    if unparsed.consumed > 1 && timer.skip_to(unparsed.consumed - 1).is_none() {
+        log::warn!(
+            "stored {} consumed, but the input has fewer segments",
+            unparsed.consumed,
+        );
+        return None;
+    }
 
-            if let Some(last_pause_time) = unparsed.last_pause {
-                // timer must also be started for it to be paused
-                if !timer.started.get_untracked() {
-                    return None;
-                }
+    // timer control methods (start, pause) set their respective properties to now.
+    // must override the times after calling these methods.
+    if let Some(duration) = unparsed.duration {
+        timer
+            .set_current_total_duration
+            .set_untracked(Some(duration.saturating_as::<i64>().milliseconds()));
+    }
 
-                timer.pause();
-                timer.last_pause_time.set_untracked(Some(timestamp::from_unix_millis(last_pause_time)));
-            }
+    if let Some(start_time) = unparsed.start {
+        timer
+            .start_time
+            .set_untracked(Some(timestamp::from_unix_millis(start_time)));
+    };
 
-            timer.acc_paused_duration = unparsed
-                .acc_pause_duration
-                .saturating_as::<i64>()
-                .milliseconds();
+    if let Some(last_pause_time) = unparsed.last_pause {
+        timer.pause();
+        timer
+            .last_pause_time
+            .set_untracked(Some(timestamp::from_unix_millis(last_pause_time)));
+    }
 
-            Some(MultiTimer(leptos::StoredValue::new(timer)))
-        })
-        .collect();
+    timer.acc_paused_duration = unparsed
+        .acc_pause_duration
+        .saturating_as::<i64>()
+        .milliseconds();
+    Some(())
+}
 
-    Some(TimerList::from_timers(timers))
+#[cfg(test)]
+mod tests {
+    use time::{ext::NumericalDuration, OffsetDateTime};
+
+    use super::*;
+    use crate::time::iso;
+
+    /// Starts a timer with `input` as if at `now`, running the segment at
+    /// `index`.
+    fn started(input: &str, now: OffsetDateTime, index: u64) -> RawMultiTimer {
+        let mut timer = RawMultiTimer::new();
+        timer.input.set_untracked(input.to_string());
+        let reference = Reference {
+            now,
+            zone: Zone::local(),
+        };
+        timer
+            .start_from(Options::default(), &Presets::default(), 1, reference)
+            .unwrap();
+        if index > 0 {
+            timer.skip_to(index).unwrap();
+        }
+        timer
+    }
+
+    /// Stores `timer` as JSON and restores it.
+    fn round_trip(timer: &RawMultiTimer, presets: &Presets) -> RawMultiTimer {
+        let json = serde_json::to_string(&TimerJson::from(timer)).unwrap();
+        restore_timer(
+            serde_json::from_str(&json).unwrap(),
+            Options::default(),
+            presets,
+        )
+    }
+
+    #[test]
+    fn restores_until() {
+        // 6 hours of timers, with only 1 hour left by now
+        let planned_at = relative::now() - 5.hours();
+        let deadline = iso::format_instant(planned_at + 6.hours());
+        let timer = started(&format!("(25m + 5m) until {deadline}"), planned_at, 20);
+        assert_eq!(timer.iter.len(), Some(24));
+
+        let restored = round_trip(&timer, &Presets::default());
+        assert!(restored.started.get_untracked());
+        assert_eq!(restored.consumed(), 21);
+        assert_eq!(restored.iter.len(), Some(24));
+        assert_eq!(
+            restored.current_total_duration.get_untracked(),
+            timer.current_total_duration.get_untracked()
+        );
+    }
+
+    #[test]
+    fn restores_invalid_unstarted() {
+        let options = Options::default();
+        let mut presets = Presets::default();
+        presets.define("pomo = 25m + 5m", options).unwrap().unwrap();
+
+        let mut timer = RawMultiTimer::new();
+        timer.input.set_untracked("pomo * 2".to_string());
+        timer.title.set_untracked("Work".to_string());
+        timer
+            .start_from(options, &presets, 1, Reference::current())
+            .unwrap();
+        assert_eq!(round_trip(&timer, &presets).consumed(), 1);
+
+        // the preset was removed since
+        let restored = round_trip(&timer, &Presets::default());
+        assert!(!restored.started.get_untracked());
+        assert_eq!(restored.input.get_untracked(), "pomo * 2");
+        assert_eq!(restored.title.get_untracked(), "Work");
+    }
+
+    #[test]
+    fn exports_current_segment() {
+        let timer = started("25m + 5m", relative::now(), 1);
+        let interval = timer.iso_interval().unwrap();
+        let (start, duration) = interval.split_once('/').unwrap();
+        assert_eq!(duration, "PT5M");
+        assert!(iso::parse_instant(start).is_some());
+
+        assert_eq!(RawMultiTimer::new().iso_interval(), None);
+    }
+
+    #[test]
+    fn restores_random_durations() {
+        let options = Options::default();
+        let mut presets = Presets::default();
+        presets
+            .define("drill = rand(3m, 7m) * 5", options)
+            .unwrap()
+            .unwrap();
+
+        let mut timer = RawMultiTimer::new();
+        timer.input.set_untracked("drill".to_string());
+        timer
+            .start_from(options, &presets, 42, Reference::current())
+            .unwrap();
+        timer.skip_to(2).unwrap();
+        let picked = timer.current_total_duration.get_untracked();
+
+        // the same seed picks the same durations for the segments to come
+        let restored = round_trip(&timer, &presets);
+        assert_eq!(restored.current_total_duration.get_untracked(), picked);
+        assert_eq!(
+            restored.iter.get(3).unwrap().duration(),
+            timer.iter.get(3).unwrap().duration()
+        );
+
+        // the current segment keeps its duration even if it would now be
+        // picked from a different range
+        presets
+            .define("drill = rand(1s, 2s) * 5", options)
+            .unwrap()
+            .unwrap();
+        let restored = round_trip(&timer, &presets);
+        assert!(restored.started.get_untracked());
+        assert_eq!(restored.consumed(), 3);
+        assert_eq!(restored.current_total_duration.get_untracked(), picked);
+    }
 }